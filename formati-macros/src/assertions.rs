@@ -0,0 +1,67 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ToTokens, quote};
+use syn::{BinOp, Expr, ExprBinary, Token, parse_macro_input, punctuated::Punctuated};
+
+use crate::formati_args::wrap_after;
+
+/// Compile an assertion's expansion only under `debug_assertions`: in release
+/// neither the condition nor the message's extracted expressions, whose
+/// bindings live inside the expansion, are emitted at all. Unlike std's
+/// `debug_assert*!`, which guards with `cfg!`, nothing is type-checked there
+/// either, so a variable only the assertion uses is unused in release
+pub fn debug_only(tokens: TokenStream) -> TokenStream {
+    let tokens = TokenStream2::from(tokens);
+    quote! {
+        {
+            #[cfg(debug_assertions)]
+            {
+                #tokens
+            };
+        }
+    }
+    .into()
+}
+
+/// `assert!` with a template message goes through `wrapped`. Without a message,
+/// a comparison condition (`a == b`, `a < b`, ...) reports both operands in the
+/// panic message, using their `Debug` where they have one
+pub fn wrap(wrapped: TokenStream2, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let comparison = match args.first() {
+        Some(Expr::Binary(binary)) if args.len() == 1 => match binary.op {
+            BinOp::Eq(_)
+            | BinOp::Ne(_)
+            | BinOp::Lt(_)
+            | BinOp::Le(_)
+            | BinOp::Gt(_)
+            | BinOp::Ge(_) => Some(binary),
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some(ExprBinary {
+        left, op, right, ..
+    }) = comparison
+    else {
+        return wrap_after(wrapped, 1, args.into_token_stream().into());
+    };
+
+    quote! {
+        match (&(#left), &(#right)) {
+            (left_val, right_val) => {
+                if !(*left_val #op *right_val) {
+                    use ::formati::__private::{ViaDebug as _, ViaOpaque as _};
+                    ::core::panic!(
+                        "assertion failed: {} (left = {:?}, right = {:?})",
+                        ::core::stringify!(#left #op #right),
+                        (&::formati::__private::Operand(left_val)).operand(),
+                        (&::formati::__private::Operand(right_val)).operand(),
+                    );
+                }
+            }
+        }
+    }
+    .into()
+}
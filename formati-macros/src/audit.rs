@@ -0,0 +1,29 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse_macro_input;
+
+use crate::formati_args::{Input, Rewritten, rewrite};
+
+/// Format with `std::format!` and pair the result with a `formati::FormatAudit`
+/// of how many placeholders were extracted and how many distinct expressions
+/// they needed
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    let Rewritten {
+        tokens,
+        placeholders,
+        unique,
+    } = match rewrite(&fmt_lit, rest) {
+        Ok(rewritten) => rewritten,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    quote! {
+        (
+            ::formati::__private::alloc::format!(#tokens),
+            ::formati::__private::format_audit(#placeholders, #unique),
+        )
+    }
+    .into()
+}
@@ -0,0 +1,25 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
+use syn::parse_macro_input;
+
+use crate::formati_args::{Input, expand};
+
+/// Build the error with `anyhow!` and return it from the enclosing function,
+/// with the call site's `file:line` attached as context
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    let wrapped = quote_spanned!(Span::call_site() => anyhow::anyhow);
+    let err = match expand(wrapped, &[], &fmt_lit, rest) {
+        Ok(err) => err,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    TokenStream::from(quote! {
+        return ::core::result::Result::Err(anyhow::Error::context(
+            #err,
+            ::core::concat!(::core::file!(), ":", ::core::line!()),
+        ))
+    })
+}
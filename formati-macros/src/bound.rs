@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{Expr, Ident, LitStr};
+
+use crate::formati_args::{
+    Directive, HeadSpans, expr_key, offset_in, parse_head, placeholder_end, raw_start,
+    reject_spec_braces, split_head_spec,
+};
+
+/// A template whose every placeholder names a value, for macros that wrap each
+/// value before formatting it. Values are bound once in a `match`, like std's
+/// `format_args!`; the caller's `name = expr` arguments are bound ahead of them,
+/// so heads can use `name`
+pub struct BoundTemplate {
+    names: Vec<Ident>,
+    named: Vec<Expr>,
+    /// Distinct placeholder expressions, by [`expr_key`] and directive
+    values: Vec<Expr>,
+    /// The literal text around the placeholders, with `{{`/`}}` still escaped;
+    /// one more piece than there are slots
+    text: Vec<String>,
+    /// Each placeholder's value and spec
+    slots: Vec<(usize, String)>,
+}
+
+impl BoundTemplate {
+    /// `what` names the macro in errors
+    pub fn parse(
+        what: &str,
+        fmt_lit: &LitStr,
+        rest: impl IntoIterator<Item = Expr>,
+    ) -> syn::Result<Self> {
+        let mut names = Vec::new();
+        let mut named = Vec::new();
+        for expr in rest {
+            let name = match &expr {
+                Expr::Assign(assign) => match &*assign.left {
+                    Expr::Path(path) => path.path.get_ident().cloned(),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match (name, expr) {
+                (Some(name), Expr::Assign(assign)) => {
+                    names.push(name);
+                    named.push(*assign.right);
+                }
+                (_, other) => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        format!(
+                            "formati!: {} take no positional arguments; write the expression in the placeholder or pass `name = expr`",
+                            what
+                        ),
+                    ));
+                }
+            }
+        }
+
+        let src = fmt_lit.value();
+        let lit = fmt_lit.token();
+        let raw_start = raw_start(&lit, &src);
+        let mut text = vec![String::new()];
+        let mut values = Vec::<Expr>::new();
+        let mut value_map = HashMap::<String, usize>::new();
+        let mut slots = Vec::new();
+        let mut rest = src.as_str();
+
+        while let Some(open) = rest.find('{') {
+            let current = text.last_mut().expect("text is never empty");
+            current.push_str(&rest[..open]);
+            rest = &rest[open..];
+            if rest.starts_with("{{") {
+                current.push_str("{{");
+                rest = &rest[2..];
+                continue;
+            }
+
+            let Some(end) = placeholder_end(rest, 1) else {
+                return Err(syn::Error::new(
+                    fmt_lit.span(),
+                    format!(
+                        "formati!: unmatched `{{` at position {}",
+                        offset_in(&src, rest)
+                    ),
+                ));
+            };
+            let piece = &rest[1..end - 1];
+            rest = &rest[end..];
+
+            let (head, spec) = split_head_spec(piece);
+            reject_spec_braces(fmt_lit, piece, spec)?;
+            if spec.contains(['$', '*']) {
+                return Err(syn::Error::new(
+                    fmt_lit.span(),
+                    format!(
+                        "formati!: `{{{}}}` takes its width or precision from an argument, which {} don't support",
+                        piece, what
+                    ),
+                ));
+            }
+
+            let head_start = raw_start.map(|start| start + offset_in(&src, head));
+            let expr = match head.trim() {
+                "" => Err(()),
+                head if head.bytes().all(|b| b.is_ascii_digit()) => Err(()),
+                _ => parse_head(head, HeadSpans::new(&lit, head, head_start)).map_err(drop),
+            }
+            .map_err(|()| {
+                syn::Error::new(
+                    fmt_lit.span(),
+                    format!(
+                        "formati!: `{{{}}}` is not an expression; {} only take placeholders naming their value",
+                        piece, what
+                    ),
+                )
+            })?;
+
+            // like `formati_args`, a directive formats a value derived from the
+            // expression and consumes the spec
+            let key = expr_key(&expr);
+            let (expr, key, spec) = match Directive::parse(fmt_lit, spec)? {
+                Some(directive) => (directive.wrap(expr), format!("{}:{}", key, spec), ""),
+                None => (expr, key, spec),
+            };
+
+            let value = *value_map.entry(key).or_insert_with(|| {
+                values.push(expr);
+                values.len() - 1
+            });
+            slots.push((value, spec.to_owned()));
+            text.push(String::new());
+        }
+        text.last_mut().expect("text is never empty").push_str(rest);
+
+        Ok(Self {
+            names,
+            named,
+            values,
+            text,
+            slots,
+        })
+    }
+
+    /// The variables each value is bound to
+    pub fn bindings(&self) -> Vec<Ident> {
+        (0..self.values.len())
+            .map(|idx| format_ident!("value_{}", idx, span = Span::mixed_site()))
+            .collect()
+    }
+
+    /// The template with each placeholder replaced by what `placeholder` makes
+    /// of its slot
+    pub fn template<'a>(
+        &'a self,
+        fmt_lit: &LitStr,
+        mut placeholder: impl FnMut(&'a (usize, String)) -> String,
+    ) -> LitStr {
+        let mut lit = self.text[0].clone();
+        for (slot, text) in self.slots.iter().zip(&self.text[1..]) {
+            lit.push_str(&placeholder(slot));
+            lit.push_str(text);
+        }
+        LitStr::new(&lit, fmt_lit.span())
+    }
+
+    /// `body` with the caller's named arguments and the values bound
+    pub fn bind(&self, body: TokenStream2) -> TokenStream2 {
+        let Self {
+            names,
+            named,
+            values,
+            ..
+        } = self;
+        let bindings = self.bindings();
+        quote! {
+            match (#(&(#named),)*) {
+                (#(#names,)*) => match (#(&(#values),)*) {
+                    (#(#bindings,)*) => #body,
+                },
+            }
+        }
+    }
+}
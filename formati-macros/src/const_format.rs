@@ -0,0 +1,117 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ToTokens, quote};
+use syn::{Expr, ExprLit, ExprUnary, Lit, LitStr, UnOp, parse_macro_input};
+
+use crate::formati_args::{placeholder_end, split_head_spec};
+
+/// Split the template into the literal text and the literal values of its
+/// placeholders, in order, as `concat!` arguments
+fn concat_args(fmt_lit: &LitStr) -> syn::Result<Vec<TokenStream2>> {
+    let src = fmt_lit.value();
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+
+    let mut rest = src.as_str();
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            text.push(c);
+            rest = &rest[2..];
+            continue;
+        }
+        if c == '}' {
+            return Err(syn::Error::new(
+                fmt_lit.span(),
+                "const_format!: unmatched `}` in template; use `}}` for a literal brace",
+            ));
+        }
+        if c != '{' {
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let Some(end) = placeholder_end(rest, 1) else {
+            return Err(syn::Error::new(
+                fmt_lit.span(),
+                "const_format!: unmatched `{` in template",
+            ));
+        };
+        let (head, spec) = split_head_spec(&rest[1..end - 1]);
+        rest = &rest[end..];
+
+        if !spec.is_empty() {
+            return Err(syn::Error::new(
+                fmt_lit.span(),
+                format!(
+                    "const_format!: format specs are not supported, found `{{{}:{}}}`",
+                    head.trim(),
+                    spec
+                ),
+            ));
+        }
+
+        let value = literal_value(head).map_err(|reason| {
+            syn::Error::new(
+                fmt_lit.span(),
+                format!("const_format!: `{{{}}}` {}", head.trim(), reason),
+            )
+        })?;
+
+        if !text.is_empty() {
+            pieces.push(LitStr::new(&text, fmt_lit.span()).into_token_stream());
+            text.clear();
+        }
+        pieces.push(value);
+    }
+
+    if !text.is_empty() || pieces.is_empty() {
+        pieces.push(LitStr::new(&text, fmt_lit.span()).into_token_stream());
+    }
+
+    Ok(pieces)
+}
+
+/// The head as a `concat!`-able literal, optionally negated, or why it isn't one
+fn literal_value(head: &str) -> Result<TokenStream2, &'static str> {
+    const NOT_LITERAL: &str =
+        "is not a literal; only string, char, integer and bool literals can be interpolated";
+
+    let expr: Expr = syn::parse_str(head).map_err(|_| NOT_LITERAL)?;
+    let lit = match &expr {
+        Expr::Lit(ExprLit { lit, .. }) => lit,
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            Expr::Lit(ExprLit {
+                lit: lit @ (Lit::Int(_) | Lit::Float(_)),
+                ..
+            }) => lit,
+            _ => return Err(NOT_LITERAL),
+        },
+        _ => return Err(NOT_LITERAL),
+    };
+
+    match lit {
+        Lit::Str(_) | Lit::Char(_) | Lit::Int(_) | Lit::Bool(_) => Ok(expr.into_token_stream()),
+        // `concat!` keeps a float as written, so `{1e3}` would read `1e3` where
+        // `Display` gives `1000`
+        Lit::Float(_) => Err(
+            "is a float literal, which `concat!` copies as written rather than as `Display` \
+             renders it; write the digits into the template instead",
+        ),
+        _ => Err(NOT_LITERAL),
+    }
+}
+
+/// Expand to `concat!` over the template's text and literal placeholders
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let fmt_lit = parse_macro_input!(input as LitStr);
+
+    match concat_args(&fmt_lit) {
+        Ok(pieces) => quote!(::core::concat!(#(#pieces),*)).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,42 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse_macro_input;
+
+use crate::formati_args::{Input, expand};
+
+/// Format with `std::format!` and convert the `String` into `target` via `From`,
+/// e.g. `Arc<str>`, so callers get the shared form without an extra clone
+pub fn wrap_into(target: TokenStream2, input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    match expand(quote!(formati::__private::alloc::format), &[], &fmt_lit, rest) {
+        Ok(formatted) => {
+            quote!(<#target as ::core::convert::From<::formati::__private::alloc::String>>::from(#formatted))
+                .into()
+        }
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Format with `std::format!`, then hand the `String` to `transform` as
+/// `__formati_s`, e.g. to change the case of the literal text and values alike
+pub fn wrap_transform(transform: TokenStream2, input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    match expand(
+        quote!(formati::__private::alloc::format),
+        &[],
+        &fmt_lit,
+        rest,
+    ) {
+        Ok(formatted) => quote! {
+            {
+                let __formati_s: ::formati::__private::alloc::String = #formatted;
+                #transform
+            }
+        }
+        .into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,44 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{LitStr, parse_macro_input};
+
+use crate::formati_args::{Input, expand};
+
+/// Format into a `Cow<'static, str>`, borrowing the literal itself when the
+/// template has no placeholders and no arguments, so nothing is allocated
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    if rest.is_empty()
+        && let Some(text) = unescape_braces(&fmt_lit.value())
+    {
+        let text = LitStr::new(&text, fmt_lit.span());
+        return quote!(::formati::__private::alloc::Cow::<'static, str>::Borrowed(#text)).into();
+    }
+
+    match expand(
+        quote!(formati::__private::alloc::format),
+        &[],
+        &fmt_lit,
+        rest,
+    ) {
+        Ok(formatted) => {
+            quote!(::formati::__private::alloc::Cow::<'static, str>::Owned(#formatted)).into()
+        }
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// The text a template without placeholders formats to, i.e. with `{{`/`}}`
+/// unescaped, or `None` if it has a placeholder (or an unmatched brace)
+fn unescape_braces(src: &str) -> Option<String> {
+    let mut text = String::with_capacity(src.len());
+    let mut chars = src.chars();
+    while let Some(c) = chars.next() {
+        if (c == '{' || c == '}') && chars.next() != Some(c) {
+            return None;
+        }
+        text.push(c);
+    }
+    Some(text)
+}
@@ -0,0 +1,83 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    Expr, Ident, Token,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+use crate::formati_args::{format_args_tokens, str_lit};
+
+/// `name = value`, where a string literal value is an interpolated template
+struct Field {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for Field {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let name = Ident::parse_any(input)?;
+        let _: Token![=] = input.parse()?;
+        let value: Expr = input.parse()?;
+        Ok(Self { name, value })
+    }
+}
+
+/// input: formatter `,` name [`,` field]* [`,`]
+struct Input {
+    formatter: Expr,
+    name: Expr,
+    fields: Punctuated<Field, Token![,]>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let formatter: Expr = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let name: Expr = input.parse()?;
+
+        let fields = if input.is_empty() {
+            Punctuated::new()
+        } else {
+            let _: Token![,] = input.parse()?;
+            Punctuated::parse_terminated(input)?
+        };
+
+        Ok(Self {
+            formatter,
+            name,
+            fields,
+        })
+    }
+}
+
+/// Expand to a `Formatter::debug_struct` chain with one `.field` per argument
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let Input {
+        formatter,
+        name,
+        fields,
+    } = parse_macro_input!(input as Input);
+
+    let mut chain = Vec::new();
+    for Field { name, value } in fields {
+        let key = name.unraw().to_string();
+        let value = match str_lit(&value) {
+            Some(fmt_lit) => match format_args_tokens(fmt_lit, []) {
+                Ok(args) => quote!(&::core::format_args!(#args)),
+                Err(err) => return err.to_compile_error().into(),
+            },
+            None => quote!(&#value),
+        };
+        chain.push(quote!(.field(#key, #value)));
+    }
+
+    quote! {
+        ::core::fmt::Formatter::debug_struct(#formatter, #name)
+            #(#chain)*
+            .finish()
+    }
+    .into()
+}
@@ -0,0 +1,45 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Expr, Token, parse_macro_input, punctuated::Punctuated};
+
+use crate::formati_args::{format_args_tokens, str_lit};
+
+/// Unwrap a `Result`, panicking with the interpolated context followed by the
+/// `Debug` of the error, which the template can also name as `err`
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let mut args = args.into_iter();
+    let Some(result) = args.next() else {
+        return syn::Error::new(Span::call_site(), "debug_unwrap!: expected a `Result`")
+            .to_compile_error()
+            .into();
+    };
+    let rest: Vec<Expr> = args.collect();
+
+    let Some(fmt_lit) = rest.first().and_then(str_lit).cloned() else {
+        return syn::Error::new(
+            Span::call_site(),
+            "debug_unwrap!: expected a context string",
+        )
+        .to_compile_error()
+        .into();
+    };
+    let tokens = match format_args_tokens(&fmt_lit, rest.into_iter().skip(1)) {
+        Ok(tokens) => tokens,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    quote! {
+        match #result {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err(err) => ::core::panic!(
+                "{}: {:?}",
+                ::core::format_args!(#tokens),
+                err
+            ),
+        }
+    }
+    .into()
+}
@@ -0,0 +1,36 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{LitStr, parse_macro_input};
+
+use crate::formati_args::{Input, expand};
+
+/// Like [`wrap`](crate::formati_args::wrap), but the template is dedented first, so only its literal text
+/// loses indentation and rendered values (and their padding) are left as is
+pub fn wrap(wrapped: TokenStream2, input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    let fmt_lit = LitStr::new(&dedent(&fmt_lit.value()), fmt_lit.span());
+    match expand(wrapped, &[], &fmt_lit, rest) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Strip a leading newline and the indentation shared by all non-blank lines;
+/// blank lines, including a trailing one before the closing quote, become empty
+fn dedent(src: &str) -> String {
+    let src = src.strip_prefix('\n').unwrap_or(src);
+    let is_blank = |line: &str| line.chars().all(|c| c == ' ' || c == '\t');
+
+    let indent = src
+        .split('\n')
+        .filter(|line| !is_blank(line))
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+
+    src.split('\n')
+        .map(|line| if is_blank(line) { "" } else { &line[indent..] })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,48 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{LitStr, parse_macro_input};
+
+use crate::bound::BoundTemplate;
+use crate::formati_args::{Input, arg_name};
+
+/// Format with `std::format!`, passing every placeholder's value through
+/// `escape` (e.g. `Escaped::html`) while the template's own text is left as is.
+///
+/// Each placeholder is rendered with its spec before it is escaped, so padding
+/// and precision still apply.
+pub fn wrap(escape: TokenStream2, input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    let bound = match BoundTemplate::parse("escaped formats", &fmt_lit, rest) {
+        Ok(bound) => bound,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // one argument per distinct value and spec
+    let mut args = Vec::<&(usize, String)>::new();
+    let lit = bound.template(&fmt_lit, |slot| {
+        let idx = match args.iter().position(|arg| *arg == slot) {
+            Some(idx) => idx,
+            None => {
+                args.push(slot);
+                args.len() - 1
+            }
+        };
+        format!("{{{}}}", arg_name(idx))
+    });
+
+    let bindings = bound.bindings();
+    let args = args.iter().enumerate().map(|(idx, (value, spec))| {
+        let name = arg_name(idx);
+        let binding = &bindings[*value];
+        let spec = LitStr::new(&format!("{{:{}}}", spec), fmt_lit.span());
+        quote!(#name = #escape(::core::format_args!(#spec, #binding)))
+    });
+
+    bound
+        .bind(quote!(
+            ::formati::__private::alloc::format!(#lit #(, #args)*)
+        ))
+        .into()
+}
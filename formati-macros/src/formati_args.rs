@@ -0,0 +1,1448 @@
+use std::collections::HashMap;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, Group, Literal, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{ToTokens, format_ident, quote, quote_spanned};
+use syn::{
+    BinOp, Expr, ExprBinary, ExprLit, Ident, Lit, LitStr, Token,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+/// input: `"literal"` [`,` expr ]*
+pub struct Input {
+    pub fmt_lit: LitStr,
+    pub rest: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        // the format string literal is always required
+        let fmt_lit: LitStr = input.parse()?;
+
+        // no arguments at all
+        if input.is_empty() {
+            return Ok(Self {
+                fmt_lit,
+                rest: Punctuated::new(),
+            });
+        }
+
+        // if we **do** see a comma, decide whether it starts a real argument list
+        // or is just a trailing comma before the right-paren / end-of-macro.
+        if input.peek(Token![,]) {
+            let _: Token![,] = input.parse()?; // eat the comma
+
+            // it's a lone trailing comma
+            if input.is_empty() {
+                return Ok(Self {
+                    fmt_lit,
+                    rest: Punctuated::new(),
+                });
+            }
+
+            // more input, parse the normal arg list
+            let rest = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+            return Ok(Self { fmt_lit, rest });
+        }
+
+        // anything else after the literal is a syntax error; let syn report it
+        Err(input.error("expected `,` or end of macro input"))
+    }
+}
+
+/// Wrap format!-like macros with formati functionality
+pub fn wrap(wrapped: TokenStream2, input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    match expand(wrapped, &[], &fmt_lit, rest) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Expand a macro that builds a `String`. Without the `alloc` feature of
+/// formati, a template that expands cleanly becomes an error naming the feature
+/// instead of an unresolved `__private::alloc` path; template errors still win.
+pub fn needs_alloc(expand: impl FnOnce() -> TokenStream) -> TokenStream {
+    let tokens = expand();
+    if cfg!(feature = "alloc") || tokens.to_string().starts_with(":: core :: compile_error") {
+        return tokens;
+    }
+    syn::Error::new(
+        Span::call_site(),
+        "formati!: this macro builds a `String` and needs the `alloc` feature of formati (or the default `std`)",
+    )
+    .to_compile_error()
+    .into()
+}
+
+/// Wrap macros whose format string comes after `leading` fixed arguments,
+/// e.g. `assert_eq!(left, right, "...")`. The message is optional, so an input
+/// without one (or with a non-literal one) is forwarded untouched.
+pub fn wrap_after(wrapped: TokenStream2, leading: usize, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let mut args = args.into_iter();
+    let front: Vec<Expr> = args.by_ref().take(leading).collect();
+    let rest: Vec<Expr> = args.collect();
+
+    match rest.first().and_then(str_lit) {
+        Some(fmt_lit) => {
+            let fmt_lit = fmt_lit.clone();
+            let front: Vec<TokenStream2> = front.iter().map(ToTokens::to_token_stream).collect();
+            match expand(wrapped, &front, &fmt_lit, rest.into_iter().skip(1)) {
+                Ok(tokens) => tokens.into(),
+                Err(err) => err.to_compile_error().into(),
+            }
+        }
+        None => TokenStream::from(quote! {
+            ::#wrapped!(#(#front ,)* #(#rest),*)
+        }),
+    }
+}
+
+/// The string literal `expr` is, looking through the invisible groups that
+/// `macro_rules!` wraps `$fmt:literal` and `$fmt:expr` fragments in
+pub fn str_lit(expr: &Expr) -> Option<&LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Some(lit),
+        Expr::Group(group) => str_lit(&group.expr),
+        _ => None,
+    }
+}
+
+/// Emit the wrapped macro call with the rewritten literal and extracted arguments
+pub fn expand(
+    wrapped: TokenStream2,
+    front: &[TokenStream2],
+    fmt_lit: &LitStr,
+    rest: impl IntoIterator<Item = Expr>,
+) -> syn::Result<TokenStream2> {
+    let args = format_args_tokens(fmt_lit, rest)?;
+
+    Ok(quote! {
+        ::#wrapped!(
+            #(#front ,)*
+            #args
+        )
+    })
+}
+
+/// Rewrite the literal and emit it followed by the positional, named and
+/// extracted arguments, in the order std's format macros expect.
+///
+/// Extracted expressions are passed as generated named arguments, so they never
+/// shift the indices of the caller's own `{}`/`{0}`/`.*` positional arguments.
+pub fn format_args_tokens(
+    fmt_lit: &LitStr,
+    rest: impl IntoIterator<Item = Expr>,
+) -> syn::Result<TokenStream2> {
+    Ok(rewrite(fmt_lit, rest)?.tokens)
+}
+
+/// A template rewritten against the caller's arguments
+pub struct Rewritten {
+    /// The literal followed by every argument, ready for a `format_args!`-like macro
+    pub tokens: TokenStream2,
+    /// How many placeholders hold a generated argument, counting repeats
+    pub placeholders: usize,
+    /// How many generated arguments there are, i.e. distinct extracted expressions
+    pub unique: usize,
+}
+
+/// See [`format_args_tokens`]. A placeholder whose head is exactly one of the
+/// caller's own arguments (e.g. `{user.id}` with `id = user.id`) refers to that
+/// argument instead of evaluating the expression a second time.
+pub fn rewrite(fmt_lit: &LitStr, rest: impl IntoIterator<Item = Expr>) -> syn::Result<Rewritten> {
+    let CallerArgs {
+        positional,
+        named,
+        reusable,
+    } = CallerArgs::split(rest)?;
+
+    let Extraction {
+        lit,
+        extracted,
+        placeholders,
+    } = formati_args(fmt_lit, &reusable)?;
+    let unique = extracted.len();
+    let lit = LitStr::new(&lit, fmt_lit.span());
+
+    let args = extracted.into_iter().enumerate().map(|(idx, extracted)| {
+        let name = arg_name(idx);
+        let value = extracted.into_value(fmt_lit.span());
+        quote!(#name = #value)
+    });
+
+    Ok(Rewritten {
+        tokens: quote! {
+            #lit
+            #(, #positional)*
+            #(, #named)*
+            #(, #args)*
+        },
+        placeholders,
+        unique,
+    })
+}
+
+/// The caller's own arguments, and which argument already holds each
+/// expression (by [`expr_key`]) so placeholders can reuse it
+pub struct CallerArgs {
+    pub positional: Vec<TokenStream2>,
+    pub named: Vec<TokenStream2>,
+    pub reusable: HashMap<String, String>,
+}
+
+impl CallerArgs {
+    pub fn split(rest: impl IntoIterator<Item = Expr>) -> syn::Result<Self> {
+        let mut named = Vec::new();
+        let mut positional = Vec::new();
+        let mut reusable = HashMap::new();
+        for expr in rest {
+            match expr {
+                Expr::Assign(assign) => {
+                    if let Expr::Path(path) = &*assign.left
+                        && let Some(name) = path.path.get_ident()
+                    {
+                        // std would take it for a duplicate of, or give its
+                        // value to, a generated argument of the same name
+                        if name.to_string().starts_with("__formati_") {
+                            return Err(syn::Error::new_spanned(
+                                name,
+                                format!(
+                                    "formati!: `{}` is reserved for extracted expressions; rename this argument",
+                                    name
+                                ),
+                            ));
+                        }
+                        reusable
+                            .entry(expr_key(&assign.right))
+                            .or_insert_with(|| name.to_string());
+                    }
+                    named.push(assign.into_token_stream());
+                }
+                x => {
+                    reusable
+                        .entry(expr_key(&x))
+                        .or_insert_with(|| positional.len().to_string());
+                    positional.push(x.into_token_stream());
+                }
+            }
+        }
+
+        Ok(Self {
+            positional,
+            named,
+            reusable,
+        })
+    }
+}
+
+/// Token-level text of an expression, so heads and arguments compare equal
+/// regardless of spacing or redundant outer parentheses
+pub fn expr_key(mut expr: &Expr) -> String {
+    while let Expr::Paren(paren) = expr {
+        expr = &paren.expr;
+    }
+    expr.to_token_stream().to_string()
+}
+
+/// Name of the generated argument holding the `idx`-th extracted expression
+pub fn arg_name(idx: usize) -> Ident {
+    format_ident!("__formati_{}", idx)
+}
+
+/// The rewritten literal and the expressions behind its generated arguments
+pub struct Extraction {
+    pub lit: String,
+    pub extracted: Vec<Extracted>,
+    pub placeholders: usize,
+}
+
+/// Process a format string for dot notation and expressions.
+///
+/// Returns the rewritten literal and the extracted expressions, in the order of
+/// their `__formati_N` names, except for heads found in `reusable`,
+/// which map an expression's [`expr_key`] to the caller argument that already
+/// holds it. Extracted heads are never re-scanned, so braces inside their
+/// string or char literals are left alone.
+pub fn formati_args(
+    fmt_lit: &LitStr,
+    reusable: &HashMap<String, String>,
+) -> syn::Result<Extraction> {
+    let src = fmt_lit.value();
+
+    // nothing to rewrite without a placeholder
+    if !src.contains('{') {
+        return Ok(Extraction {
+            lit: src,
+            extracted: Vec::new(),
+            placeholders: 0,
+        });
+    }
+
+    let mut out_lit = String::with_capacity(src.len());
+    let mut extracted = Vec::<Extracted>::new();
+    let mut placeholders = 0;
+    let mut expr_map: HashMap<String, usize> = HashMap::new();
+    let mut discards = Vec::<(usize, usize)>::new();
+
+    let lit = fmt_lit.token();
+    let raw_start = raw_start(&lit, &src);
+    let bytes = src.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' if bytes.get(i + 1) == Some(&b'{') => {
+                out_lit.push_str("{{");
+                i += 2;
+            }
+            b'}' if bytes.get(i + 1) == Some(&b'}') => {
+                out_lit.push_str("}}");
+                i += 2;
+            }
+            b'}' => {
+                return Err(syn::Error::new(
+                    fmt_lit.span(),
+                    format!(
+                        "formati!: unmatched `}}` at position {}; use `}}}}` for a literal brace",
+                        i
+                    ),
+                ));
+            }
+            b'{' => {
+                let start_inner = i + 1;
+                let Some(j) = placeholder_end(&src, start_inner) else {
+                    // the nested `{` of a spec is what swallowed the closing `}`
+                    let (_, spec) = split_head_spec(&src[start_inner..]);
+                    let message = match spec.contains('{') {
+                        true => format!(
+                            "formati!: unterminated `{{` in the spec of the placeholder at position {}",
+                            i
+                        ),
+                        false => format!("formati!: unmatched `{{` at position {}", i),
+                    };
+                    return Err(syn::Error::new(fmt_lit.span(), message));
+                };
+
+                let piece = &src[start_inner..j - 1];
+                i = j;
+
+                let (head, spec) = split_head_spec(piece);
+                reject_spec_braces(fmt_lit, piece, spec)?;
+                reject_dotted_number(fmt_lit, head)?;
+                reject_attributes(fmt_lit, head)?;
+
+                // `{_ = expr}` evaluates `expr` (shared with other placeholders
+                // holding it) without writing anything
+                let (head, discard) = match discard_head(head) {
+                    Some(expr) if spec.is_empty() => (expr, true),
+                    Some(_) => {
+                        return Err(syn::Error::new(
+                            fmt_lit.span(),
+                            format!(
+                                "formati!: `{{{}}}` discards its value, so it takes no spec",
+                                piece
+                            ),
+                        ));
+                    }
+                    None => (head, false),
+                };
+                let directive = Directive::parse(fmt_lit, spec)?;
+
+                if discard || directive.is_some() || should_extract_expression(head) {
+                    // Try to parse the expression - if it fails, treat as regular placeholder
+                    let head_start = raw_start.map(|start| start + offset_in(&src, head));
+                    match parse_head(head, HeadSpans::new(&lit, head, head_start)) {
+                        Ok(expr) => {
+                            // deduplicate on the tokens, so spacing doesn't matter.
+                            // `{items:join(..)}` formats the joined items (likewise
+                            // `time(..)`), and never reuses a caller argument
+                            // holding the bare value
+                            let key = expr_key(&expr);
+                            let (expr, key, spec) = match &directive {
+                                Some(directive) => {
+                                    (directive.wrap(expr), format!("{}:{}", key, spec), "")
+                                }
+                                None => (expr, key, spec),
+                            };
+
+                            // the caller already passes this exact expression
+                            if !discard
+                                && directive.is_none()
+                                && let Some(name) = reusable.get(&key)
+                            {
+                                out_lit.push('{');
+                                out_lit.push_str(name);
+                                if !spec.is_empty() {
+                                    out_lit.push(':');
+                                    out_lit.push_str(spec);
+                                }
+                                out_lit.push('}');
+                                continue;
+                            }
+
+                            // Successfully parsed - extract it
+                            let idx = match expr_map.get(&key) {
+                                Some(&idx) => idx,
+                                None => {
+                                    let idx = extracted.len();
+                                    expr_map.insert(key, idx);
+                                    extracted.push(Extracted {
+                                        expr,
+                                        checks: Vec::new(),
+                                    });
+                                    idx
+                                }
+                            };
+
+                            placeholders += 1;
+
+                            // filled in once we know whether anything displays it
+                            if discard {
+                                discards.push((out_lit.len(), idx));
+                                continue;
+                            }
+
+                            let check = spec_check(spec);
+                            if !extracted[idx].checks.contains(&check) {
+                                extracted[idx].checks.push(check);
+                            }
+
+                            // replace with named `{__formati_idx[:spec]}` placeholder
+                            out_lit.push('{');
+                            out_lit.push_str(&arg_name(idx).to_string());
+                            if !spec.is_empty() {
+                                out_lit.push(':');
+                                out_lit.push_str(spec);
+                            }
+                            out_lit.push('}');
+                        }
+                        Err(_) if discard || directive.is_some() => {
+                            let action = match &directive {
+                                Some(Directive::Join(_)) => "join",
+                                Some(Directive::Time(_)) => "format as a time",
+                                Some(Directive::Unit(_)) => "format as a duration",
+                                Some(Directive::Style(_)) => "style",
+                                Some(Directive::Chain) => "format as an error chain",
+                                Some(Directive::Formattable) => "format with `Formattable`",
+                                None => "discard",
+                            };
+                            return Err(syn::Error::new(
+                                fmt_lit.span(),
+                                format!(
+                                    "formati!: `{}` is not an expression to {}",
+                                    head.trim(),
+                                    action
+                                ),
+                            ));
+                        }
+                        Err(_) => {
+                            // Failed to parse - keep as regular placeholder
+                            reject_generated_name(fmt_lit, head)?;
+                            out_lit.push('{');
+                            out_lit.push_str(piece);
+                            out_lit.push('}');
+                        }
+                    }
+                } else {
+                    // keep original placeholder verbatim
+                    reject_generated_name(fmt_lit, head)?;
+                    out_lit.push('{');
+                    out_lit.push_str(piece);
+                    out_lit.push('}');
+                }
+            }
+            _ => {
+                // copy the whole character; a multi-byte one must not be split
+                let len = src[i..].chars().next().map_or(1, char::len_utf8);
+                out_lit.push_str(&src[i..i + len]);
+                i += len;
+            }
+        }
+    }
+
+    // an expression that is only ever discarded still needs a placeholder, or
+    // std rejects its argument as unused; it is passed as `Discard`, which
+    // writes nothing (see `Extracted::into_value`)
+    let mut lit = String::with_capacity(out_lit.len());
+    let mut copied = 0;
+    for &(pos, idx) in &discards {
+        if extracted[idx].checks.is_empty() {
+            lit.push_str(&out_lit[copied..pos]);
+            lit.push_str(&format!("{{{}}}", arg_name(idx)));
+            copied = pos;
+        }
+    }
+    lit.push_str(&out_lit[copied..]);
+
+    Ok(Extraction {
+        lit,
+        extracted,
+        placeholders,
+    })
+}
+
+/// The expression of a `_ = expr` discard head
+fn discard_head(head: &str) -> Option<&str> {
+    let rest = head.trim_start().strip_prefix('_')?.trim_start();
+    let expr = rest.strip_prefix('=')?;
+    (!expr.starts_with('=')).then_some(expr.trim())
+}
+
+/// A spec that formats a value derived from the placeholder's expression
+/// rather than the expression itself
+pub enum Directive {
+    Join(JoinSpec),
+    Time(LitStr),
+    Unit(DurationUnit),
+    Style(String),
+    Chain,
+    Formattable,
+}
+
+impl Directive {
+    pub fn parse(fmt_lit: &LitStr, spec: &str) -> syn::Result<Option<Self>> {
+        if let Some(join) = join_spec(fmt_lit, spec)? {
+            return Ok(Some(Self::Join(join)));
+        }
+        if let Some(fmt) = time_spec(fmt_lit, spec)? {
+            return Ok(Some(Self::Time(fmt)));
+        }
+        if spec == "chain" {
+            return Ok(Some(Self::Chain));
+        }
+        if spec == "*" {
+            return Ok(Some(Self::Formattable));
+        }
+        if let Some(escape) = style_spec(fmt_lit, spec)? {
+            return Ok(Some(Self::Style(escape)));
+        }
+        Ok(unit_spec(fmt_lit, spec)?.map(Self::Unit))
+    }
+
+    /// The value formatted in place of `expr`
+    pub fn wrap(&self, expr: Expr) -> Expr {
+        match self {
+            Self::Join(join) => join.wrap(expr),
+            Self::Time(fmt) => syn::parse_quote! {
+                {
+                    use ::formati::__private::FormatTime as _;
+                    (&(#expr)).formati_time(#fmt)
+                }
+            },
+            Self::Chain => syn::parse_quote! {
+                {
+                    use ::formati::__private::FormatChain as _;
+                    (&(#expr)).formati_chain()
+                }
+            },
+            Self::Formattable => syn::parse_quote! {
+                {
+                    use ::formati::__private::Formattable as _;
+                    (&(#expr)).formati()
+                }
+            },
+            Self::Style(escape) => syn::parse_quote! {
+                ::formati::__private::Styled::new(&(#expr), #escape)
+            },
+            Self::Unit(DurationUnit {
+                nanos_per_unit,
+                precision,
+            }) => {
+                let precision = match precision {
+                    Some(precision) => quote!(::core::option::Option::Some(#precision)),
+                    None => quote!(::core::option::Option::None),
+                };
+                syn::parse_quote! {
+                    ::formati::__private::DurationIn::new(&(#expr), #nanos_per_unit, #precision)
+                }
+            }
+        }
+    }
+}
+
+/// SGR codes of the `{x:red}` / `{x:red.bold}` style names
+const STYLES: [(&str, u8); 12] = [
+    ("bold", 1),
+    ("dim", 2),
+    ("italic", 3),
+    ("underline", 4),
+    ("black", 30),
+    ("red", 31),
+    ("green", 32),
+    ("yellow", 33),
+    ("blue", 34),
+    ("magenta", 35),
+    ("cyan", 36),
+    ("white", 37),
+];
+
+/// The ANSI escape that opens a spec made only of `.`-separated style names,
+/// which needs the `color` feature
+fn style_spec(fmt_lit: &LitStr, spec: &str) -> syn::Result<Option<String>> {
+    let codes: Option<Vec<String>> = spec
+        .split('.')
+        .map(|name| {
+            STYLES
+                .iter()
+                .find(|(style, _)| *style == name)
+                .map(|(_, code)| code.to_string())
+        })
+        .collect();
+    let Some(codes) = codes else {
+        return Ok(None);
+    };
+
+    if !cfg!(feature = "color") {
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            format!("formati!: `{}` needs the `color` feature of formati", spec),
+        ));
+    }
+
+    Ok(Some(format!("\x1b[{}m", codes.join(";"))))
+}
+
+/// A `{elapsed:ms}` / `{elapsed:ms.2}` spec rendering a `Duration` in one unit
+pub struct DurationUnit {
+    nanos_per_unit: u32,
+    precision: Option<usize>,
+}
+
+const DURATION_UNITS: [(&str, u32); 4] = [
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// The names of a spec table, e.g. "`s`, `ms`, `us`, `ns`"
+fn quoted_names<T>(table: &[(&str, T)]) -> String {
+    let names: Vec<String> = table
+        .iter()
+        .map(|(name, _)| format!("`{}`", name))
+        .collect();
+    names.join(", ")
+}
+
+/// A spec naming a duration unit, optionally followed by `.precision`. Any
+/// other lowercase spec that isn't one of std's format traits is unknown.
+fn unit_spec(fmt_lit: &LitStr, spec: &str) -> syn::Result<Option<DurationUnit>> {
+    let (unit, precision) = match spec.split_once('.') {
+        Some((unit, precision)) => (unit, Some(precision)),
+        None => (spec, None),
+    };
+    if unit.is_empty()
+        || !unit.bytes().all(|b| b.is_ascii_lowercase())
+        || ["b", "e", "o", "p", "x"].contains(&unit)
+    {
+        return Ok(None);
+    }
+
+    let Some(&(_, nanos_per_unit)) = DURATION_UNITS.iter().find(|(name, _)| *name == unit) else {
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            format!(
+                "formati!: unknown spec `{}`; expected a duration unit ({}) or `.`-separated styles ({})",
+                spec,
+                quoted_names(&DURATION_UNITS),
+                quoted_names(&STYLES),
+            ),
+        ));
+    };
+
+    let precision = match precision {
+        None => None,
+        Some(digits) => Some(digits.parse().map_err(|_| {
+            syn::Error::new(
+                fmt_lit.span(),
+                format!(
+                    "formati!: expected a precision after `{}.`, found `{}`",
+                    unit, digits
+                ),
+            )
+        })?),
+    };
+
+    Ok(Some(DurationUnit {
+        nanos_per_unit,
+        precision,
+    }))
+}
+
+/// The strftime-like format string of a `time(fmt)` placeholder spec, which
+/// needs `chrono` at runtime
+fn time_spec(fmt_lit: &LitStr, spec: &str) -> syn::Result<Option<LitStr>> {
+    let Some(args) = spec.strip_prefix("time(") else {
+        return Ok(None);
+    };
+
+    if !cfg!(feature = "chrono") {
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            format!("formati!: `{}` needs the `chrono` feature of formati", spec),
+        ));
+    }
+
+    let Some(fmt) = args.strip_suffix(')') else {
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            format!(
+                "formati!: expected `time(format)` with nothing after the `)`, found `{}`",
+                spec
+            ),
+        ));
+    };
+
+    // an unknown directive would make chrono fail at runtime, panicking `format!`
+    #[cfg(feature = "chrono")]
+    if chrono::format::StrftimeItems::new(fmt)
+        .any(|item| matches!(item, chrono::format::Item::Error))
+    {
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            format!(
+                "formati!: `{}` has an unknown or incomplete `%` directive; see chrono's `strftime` docs",
+                spec
+            ),
+        ));
+    }
+
+    Ok(Some(LitStr::new(fmt, fmt_lit.span())))
+}
+
+/// A `join("sep")[:spec]` placeholder spec: the items are written with
+/// `spec` (or plain `Display`) and separated by `sep`
+pub struct JoinSpec {
+    sep: LitStr,
+    item_spec: String,
+}
+
+impl JoinSpec {
+    /// The value formatted in place of the iterable `expr`
+    fn wrap(&self, expr: Expr) -> Expr {
+        let Self { sep, item_spec } = self;
+        let item_fmt = LitStr::new(&format!("{{{}}}", item_spec), sep.span());
+        syn::parse_quote! {
+            ::formati::__private::Join::new(
+                &(#expr),
+                #sep,
+                |item, f| ::core::write!(f, #item_fmt, item),
+            )
+        }
+    }
+}
+
+/// Parse a `join("sep")` spec, optionally followed by `:item_spec`. Specs not
+/// starting with `join(` are ordinary std specs, since no std spec can.
+fn join_spec(fmt_lit: &LitStr, spec: &str) -> syn::Result<Option<JoinSpec>> {
+    let Some(args) = spec.strip_prefix("join(") else {
+        return Ok(None);
+    };
+
+    let malformed = || {
+        syn::Error::new(
+            fmt_lit.span(),
+            format!(
+                "formati!: expected `join(\"separator\")` optionally followed by `:spec`, found `{}`",
+                spec
+            ),
+        )
+    };
+
+    // the separator is a string literal, which may itself contain `)` or `:`
+    let close = string_literal_end(args).ok_or_else(malformed)?;
+    let sep: LitStr = syn::parse_str(&args[..close]).map_err(|_| malformed())?;
+    let rest = args[close..]
+        .trim_start()
+        .strip_prefix(')')
+        .ok_or_else(malformed)?;
+
+    let item_spec = match rest {
+        "" => String::new(),
+        _ => format!(":{}", rest.strip_prefix(':').ok_or_else(malformed)?),
+    };
+
+    Ok(Some(JoinSpec {
+        sep: LitStr::new(&sep.value(), fmt_lit.span()),
+        item_spec,
+    }))
+}
+
+/// Byte index just past the `"..."` literal `s` starts with (after whitespace)
+fn string_literal_end(s: &str) -> Option<usize> {
+    let start = s.len() - s.trim_start().len();
+    let mut chars = s[start..].char_indices();
+    if chars.next()?.1 != '"' {
+        return None;
+    }
+    let mut escaped = false;
+    for (idx, c) in chars {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(start + idx + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// An extracted expression and the formatting traits its placeholders need
+pub struct Extracted {
+    pub expr: Expr,
+    pub checks: Vec<&'static str>,
+}
+
+impl Extracted {
+    /// The value passed for the generated argument. With the `strict` feature the
+    /// expression goes through identity functions bounded by each trait it is
+    /// formatted with, so a missing impl is reported against the expression
+    /// rather than deep inside `format_args!`.
+    pub fn into_value(self, span: Span) -> TokenStream2 {
+        let Self { expr, checks } = self;
+
+        // only ever discarded: evaluated, but formats to nothing
+        if checks.is_empty() {
+            return quote_spanned!(span=> ::formati::__private::Discard(&(#expr)));
+        }
+
+        // `{:p}` formats the reference itself, so it can't be passed through a `&T`
+        if !cfg!(feature = "strict") || checks.contains(&"pointer") {
+            return expr.into_token_stream();
+        }
+
+        checks
+            .into_iter()
+            .fold(quote_spanned!(span=> &(#expr)), |value, check| {
+                let check = format_ident!("{}", check, span = span);
+                quote_spanned!(span=> ::formati::__private::strict::#check(#value))
+            })
+    }
+}
+
+/// Name of the `formati::__private::strict` check for the trait `spec` formats with
+fn spec_check(spec: &str) -> &'static str {
+    match spec.chars().last() {
+        Some('?') => "debug",
+        Some('x') => "lower_hex",
+        Some('X') => "upper_hex",
+        Some('o') => "octal",
+        Some('b') => "binary",
+        Some('e') => "lower_exp",
+        Some('E') => "upper_exp",
+        Some('p') => "pointer",
+        _ => "display",
+    }
+}
+
+/// Parse a placeholder head, giving its identifiers the template literal's span
+/// so they resolve in the caller's scope (like std's implicit captures) even
+/// when the literal was forwarded through a `macro_rules!`.
+///
+/// Other tokens keep the macro's span so lints treat the expression as generated,
+/// except under the `strict` feature, where every token points at the literal
+/// so trait errors are reported there instead of at the whole macro call.
+///
+/// A `value | |v| render(v)` head (a closure on the right of a top-level `|`,
+/// which no `BitOr` impl accepts) formats what the closure returns for a
+/// borrow of `value`.
+pub fn parse_head(head: &str, mut spans: HeadSpans) -> syn::Result<Expr> {
+    let tokens: TokenStream2 = head.parse()?;
+    let expr = syn::parse2(respan(tokens, &mut spans, cfg!(feature = "strict")))?;
+
+    Ok(match expr {
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::BitOr(_),
+            right,
+            ..
+        }) if matches!(*right, Expr::Closure(_)) => {
+            syn::parse_quote!(::formati::__private::pipe(&(#left), #right))
+        }
+        expr => expr,
+    })
+}
+
+/// Where the template's text starts inside the literal's source, e.g. `1` for
+/// `"..."` and `3` for `r#"..."#`. `None` when escapes make the source differ
+/// from the value, as offsets into the value would no longer line up.
+pub fn raw_start(lit: &Literal, value: &str) -> Option<usize> {
+    let repr = lit.to_string();
+    let start = if let Some(raw) = repr.strip_prefix('r') {
+        let hashes = raw.bytes().take_while(|&b| b == b'#').count();
+        2 + hashes
+    } else {
+        1
+    };
+    (repr.get(start..start + value.len()) == Some(value)).then_some(start)
+}
+
+/// Byte offset of `part` within `whole`, which it must be a slice of
+pub fn offset_in(whole: &str, part: &str) -> usize {
+    part.as_ptr() as usize - whole.as_ptr() as usize
+}
+
+/// Spans for a head's tokens, pointing at their text inside the template
+/// literal where the compiler supports `Literal::subspan` (nightly), and at
+/// the whole literal otherwise.
+///
+/// Field and method names, which resolve the same in any context, get a
+/// whole-literal span marked as macro-generated, so rustc offers no fix-it for
+/// a misspelt one: it would replace the entire template with the suggestion.
+pub struct HeadSpans<'a> {
+    lit: &'a Literal,
+    fallback: Span,
+    member: Span,
+    head: &'a str,
+    start: Option<usize>,
+    cursor: usize,
+}
+
+impl<'a> HeadSpans<'a> {
+    pub fn new(lit: &'a Literal, head: &'a str, start: Option<usize>) -> Self {
+        Self {
+            lit,
+            fallback: lit.span(),
+            member: lit.span().resolved_at(Span::call_site()),
+            head,
+            start,
+            cursor: 0,
+        }
+    }
+
+    /// Span of the next occurrence of `text`, scanning the head left to right
+    /// in token order; `member` for a field or method name
+    fn next(&mut self, text: &str, member: bool) -> Span {
+        let fallback = if member { self.member } else { self.fallback };
+        let Some(pos) = self.head[self.cursor..].find(text) else {
+            return fallback;
+        };
+        let from = self.cursor + pos;
+        self.cursor = from + text.len();
+        self.start
+            .and_then(|start| self.lit.subspan(start + from..start + self.cursor))
+            .unwrap_or(fallback)
+    }
+
+    /// Span of the whole head
+    fn head(&self) -> Span {
+        self.start
+            .and_then(|start| self.lit.subspan(start..start + self.head.len()))
+            .unwrap_or(self.fallback)
+    }
+}
+
+/// Give idents (or, with `all`, every token) the span of their text in the
+/// literal, so they resolve and report errors at the template
+fn respan(tokens: TokenStream2, spans: &mut HeadSpans, all: bool) -> TokenStream2 {
+    // a name after exactly one `.` is a field or method, not the end of a range
+    let mut dots = 0;
+    tokens
+        .into_iter()
+        .map(|tt| {
+            let member = dots == 1;
+            dots = match &tt {
+                TokenTree::Punct(punct) if punct.as_char() == '.' => dots + 1,
+                _ => 0,
+            };
+            match tt {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::None => ("", ""),
+                    };
+                    spans.next(open, false);
+                    let stream = respan(group.stream(), spans, all);
+                    spans.next(close, false);
+
+                    let mut respanned = Group::new(group.delimiter(), stream);
+                    respanned.set_span(if all { spans.head() } else { group.span() });
+                    TokenTree::Group(respanned)
+                }
+                TokenTree::Ident(mut ident) => {
+                    ident.set_span(spans.next(&ident.to_string(), member));
+                    TokenTree::Ident(ident)
+                }
+                mut tt => {
+                    let span = spans.next(&tt.to_string(), false);
+                    if all {
+                        tt.set_span(span);
+                    }
+                    tt
+                }
+            }
+        })
+        .collect()
+}
+
+/// Placeholders left for std to resolve must not name one of the generated
+/// `__formati_N` arguments, whose numbering is an implementation detail
+fn reject_generated_name(fmt_lit: &LitStr, head: &str) -> syn::Result<()> {
+    let head = head.trim();
+    if head.starts_with("__formati_") {
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            format!("formati!: `{}` is reserved for extracted expressions", head),
+        ));
+    }
+    Ok(())
+}
+
+/// A head of only digits and dots that isn't a float (`{1.2.3}`) would
+/// otherwise parse as field access on a float literal
+fn reject_dotted_number(fmt_lit: &LitStr, head: &str) -> syn::Result<()> {
+    let head = head.trim();
+    if head.contains('.')
+        && head.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && head.parse::<f64>().is_err()
+    {
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            format!("formati!: `{}` is not a valid argument or expression", head),
+        ));
+    }
+    Ok(())
+}
+
+/// A `#[cfg]`-ed out head would leave its placeholder without a value, so
+/// attributed expressions are rejected rather than extracted
+fn reject_attributes(fmt_lit: &LitStr, head: &str) -> syn::Result<()> {
+    if head.trim_start().starts_with('#') {
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            "formati!: attributes are not supported inside placeholders; \
+             bind the value with a `#[cfg]`-gated `let` and format that instead",
+        ));
+    }
+    Ok(())
+}
+
+/// Error on a `{` or `}` in a spec outside a string literal (such as a
+/// `join` separator): std specs never nest placeholders
+pub fn reject_spec_braces(fmt_lit: &LitStr, piece: &str, spec: &str) -> syn::Result<()> {
+    let mut rest = spec;
+    while let Some(idx) = rest.find(['"', '{', '}']) {
+        if rest[idx..].starts_with('"') {
+            let Some(end) = string_literal_end(&rest[idx..]) else {
+                return Ok(());
+            };
+            rest = &rest[idx + end..];
+            continue;
+        }
+        return Err(syn::Error::new(
+            fmt_lit.span(),
+            format!(
+                "formati!: unexpected `{}` in the spec of `{{{}}}`; \
+                 use `name$` to take a width or precision from an argument",
+                &rest[idx..idx + 1],
+                piece
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Find the end of the placeholder whose contents start at `start_inner`,
+/// properly handling nested braces. Returns the index just past the closing `}`,
+/// or `None` if the placeholder is never closed.
+pub fn placeholder_end(src: &str, start_inner: usize) -> Option<usize> {
+    let bytes = src.as_bytes();
+    let mut j = start_inner;
+    let mut depth = 1;
+    let mut in_string = false;
+    let mut escape_next = false;
+
+    while j < bytes.len() && depth != 0 {
+        let ch = bytes[j] as char;
+
+        if escape_next {
+            escape_next = false;
+            j += 1;
+            continue;
+        }
+
+        match ch {
+            '\\' if in_string => {
+                escape_next = true;
+            }
+            '"' => {
+                in_string = !in_string;
+            }
+            // skip a char literal whole; a lifetime or label is just text
+            '\'' if !in_string => {
+                if let Some(end) = char_literal_end(src, j) {
+                    j = end;
+                    continue;
+                }
+            }
+            '{' if !in_string => {
+                depth += 1;
+            }
+            '}' if !in_string => {
+                depth -= 1;
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+
+    (depth == 0).then_some(j)
+}
+
+/// End of the char literal whose opening `'` is at byte `quote`, or `None` when
+/// the `'` starts a lifetime or label instead. As in the lexer, `'\` opens an
+/// escape (`'\''`, `'\u{7b}'`) and otherwise exactly one char must come before
+/// the closing `'`, so `'a'` is a char but `'a` and `'outer: {` are not
+fn char_literal_end(s: &str, quote: usize) -> Option<usize> {
+    let rest = &s[quote + 1..];
+    let mut chars = rest.char_indices();
+    match chars.next()? {
+        (_, '\\') => {
+            let (escaped, c) = chars.next()?;
+            // `\x7f` and `\u{..}` are the only escapes longer than one char
+            let end = match c {
+                'x' => escaped + 3,
+                'u' => escaped + rest[escaped..].find('}')? + 1,
+                c => escaped + c.len_utf8(),
+            };
+            rest.get(end..)?
+                .starts_with('\'')
+                .then(|| quote + 1 + end + 1)
+        }
+        (_, c) => rest[c.len_utf8()..]
+            .starts_with('\'')
+            .then(|| quote + 1 + c.len_utf8() + 1),
+    }
+}
+
+// split `HEAD[:SPEC]`, ignoring `::` (path separators) and handling complex expressions.
+// An explicit `HEAD ; SPEC` separator takes precedence over the `:` heuristics
+pub fn split_head_spec(s: &str) -> (&str, &str) {
+    if let Some(idx) = explicit_separator(s) {
+        return (s[..idx].trim_end(), s[idx + 1..].trim_start());
+    }
+
+    let mut chars = s.char_indices().peekable();
+    let mut paren_depth = 0;
+    let mut bracket_depth = 0;
+    let mut brace_depth = 0;
+    let mut angle_depth = 0;
+    let mut in_string = false;
+    let mut escape_next = false;
+
+    while let Some((idx, c)) = chars.next() {
+        if escape_next {
+            escape_next = false;
+            continue;
+        }
+
+        match c {
+            '\\' if in_string => {
+                escape_next = true;
+            }
+            '"' => {
+                in_string = !in_string;
+            }
+            '\'' if !in_string => match char_literal_end(s, idx) {
+                // skip the char literal, whatever it holds (`':'`, `'{'`, ...)
+                Some(end) => while chars.next_if(|&(next, _)| next < end).is_some() {},
+                // a lifetime or label; the `:` after a label (`'outer: loop`)
+                // is part of the expression
+                None => {
+                    while chars
+                        .next_if(|&(_, c)| c.is_alphanumeric() || c == '_')
+                        .is_some()
+                    {}
+                    let mut rest = chars.clone().map(|(_, c)| c);
+                    if rest.next() == Some(':') && rest.next() != Some(':') {
+                        chars.next();
+                    }
+                }
+            },
+            _ if in_string => {
+                continue;
+            }
+            '(' => paren_depth += 1,
+            ')' => paren_depth -= 1,
+            '[' => bracket_depth += 1,
+            ']' => bracket_depth -= 1,
+            '{' => brace_depth += 1,
+            '}' => brace_depth -= 1,
+            // More sophisticated generic detection
+            '<' if should_count_as_generic(s, idx) => angle_depth += 1,
+            // generics can't contain `=>`, so any `<` still open was a comparison
+            '>' if s[..idx].ends_with('=') => angle_depth = 0,
+            // `->` (e.g. `Fn() -> T`) and `>=` never close generics
+            '>' if s[..idx].ends_with('-') || s[idx + 1..].starts_with('=') => {}
+            '>' if angle_depth > 0 => angle_depth -= 1,
+            ':' if paren_depth == 0
+                && bracket_depth == 0
+                && brace_depth == 0
+                && angle_depth == 0 =>
+            {
+                // Check if this is part of '::'
+                if let Some((_, ':')) = chars.peek() {
+                    chars.next(); // consume the second ':'
+                    continue;
+                }
+                // Found a format specifier separator
+                return (&s[..idx], &s[idx + 1..]);
+            }
+            _ => {}
+        }
+    }
+
+    (s, "")
+}
+
+/// Find a top-level `;`, which can never appear in an expression itself, skipping
+/// string and char literals and anything nested in brackets
+fn explicit_separator(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            // char literals; a lone `'` is a lifetime or label
+            b'\'' => {
+                if let Some(end) = char_literal_end(s, i) {
+                    i = end;
+                    continue;
+                }
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            b';' if depth == 0 => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
+fn should_count_as_generic(s: &str, idx: usize) -> bool {
+    if idx == 0 {
+        return false;
+    }
+
+    // `<<` and `<=` are operators whatever precedes them
+    if matches!(s[idx + 1..].chars().next(), Some('<') | Some('=')) {
+        return false;
+    }
+
+    match s[..idx].chars().next_back() {
+        // Definitely generic contexts
+        Some(c) if c.is_alphanumeric() || c == '_' => true, // identifier
+        Some(':') => true,                                  // ::< or :
+        Some('>') => true,                                  // nested generics Type<U>
+
+        // Definitely comparison contexts
+        Some('=') | Some('!') | Some('<') => false, // ==<, !=<, <<, etc.
+
+        // Whitespace - need deeper analysis
+        Some(' ') | Some('\t') | Some('\n') => is_likely_generic_with_space(s, idx),
+
+        // Default to comparison for safety
+        _ => false,
+    }
+}
+
+fn is_likely_generic_with_space(s: &str, idx: usize) -> bool {
+    // Look backward to find the last non-whitespace token
+    let before_whitespace = s[..idx].trim_end();
+
+    if before_whitespace.is_empty() {
+        return false;
+    }
+
+    // Check if it ends with something that could take generics
+    let last_token = before_whitespace.split_whitespace().last().unwrap_or("");
+
+    // Pattern matching for likely generic contexts
+    last_token
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == ':')
+        && (last_token.contains("::") ||
+    last_token.chars().next().is_some_and(|c| c.is_uppercase()) || // PascalCase
+    last_token.ends_with("_t") || // C-style type suffixes
+    last_token.len() > 1) // Avoid single letters which are usually variables
+}
+
+fn should_extract_expression(head: &str) -> bool {
+    // Don't extract if it's just a simple identifier or number
+    if head.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return false;
+    }
+
+    // Don't extract simple literals
+    if head.parse::<i64>().is_ok() || head.parse::<f64>().is_ok() {
+        return false;
+    }
+
+    // Extract if it contains dots, method calls, array indexing, references, or complex expressions
+    head.contains('.')
+       || head.contains("::")
+       || head.contains('(')
+       || head.contains('[')
+       || head.contains('<')
+       || head.contains("r#")         // Raw identifiers, which `format_args!` rejects
+       || head.starts_with('&')       // Reference expressions
+       || head.starts_with("mut ")    // Mutable references
+       || head.starts_with("&mut ")   // &mut expressions
+       || head.starts_with('*')       // Dereference expressions
+       || head.contains(" as ")       // Type casting
+       || head.contains('?')          // Try operator
+       || head.contains("..")         // Range expressions
+       || head.contains('{')          // Struct literals, closures, blocks
+       || head.contains(" if ")       // if expressions
+       || head.contains(" match ")    // match expressions
+       || is_complex_expression(head) // More sophisticated detection
+}
+
+fn is_complex_expression(head: &str) -> bool {
+    // Check for operators that indicate complex expressions
+    let operators = [
+        "+", "-", "*", "/", "%", // Arithmetic
+        "==", "!=", "<", ">", "<=", ">=", // Comparison
+        "&&", "||", "!", // Logical
+        "&", "|", "^", "<<", ">>", // Bitwise
+        "=", "+=", "-=", "*=", "/=", // Assignment
+    ];
+
+    // Check for operators (but be careful about false positives in strings)
+    let mut in_string = false;
+    let mut escape_next = false;
+
+    for (idx, c) in head.char_indices() {
+        if escape_next {
+            escape_next = false;
+            continue;
+        }
+
+        match c {
+            '\\' if in_string => {
+                escape_next = true;
+            }
+            '"' => {
+                in_string = !in_string;
+            }
+            _ if in_string => {
+                continue;
+            }
+            _ => {
+                // Operator characters can't be part of an identifier, so any
+                // occurrence counts, even without surrounding spaces (`{a+b}`)
+                let remaining = &head[idx..];
+                if operators.iter().any(|op| remaining.starts_with(op)) {
+                    return true;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// Scaling benchmarks for large templates, run with
+/// `cargo test -p formati-macros --release --lib -- --ignored --nocapture`.
+/// Each size is ten times the previous one, and so should be its time
+#[cfg(test)]
+mod tests {
+    use super::{rewrite, split_head_spec};
+    use proc_macro2::Span;
+    use std::time::Instant;
+    use syn::LitStr;
+
+    /// A single head of `terms` comparisons and generic calls, all separated by
+    /// whitespace, then a spec. Splitting it should take time linear in its length
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_split_long_head() {
+        for terms in [1_000, 10_000, 100_000] {
+            let mut head = String::from("0");
+            for _ in 0..terms {
+                head.push_str(" + (count < limit) as usize + Vec::<u8> ::new().len()");
+            }
+            let s = format!("{head}:>8");
+
+            let start = Instant::now();
+            assert_eq!(split_head_spec(&s), (head.as_str(), ">8"));
+            println!(
+                "{terms:>7} terms, {:>8} bytes: {:?}",
+                s.len(),
+                start.elapsed()
+            );
+        }
+    }
+
+    /// A 2KB head made of every construct the splitter looks at: generics,
+    /// comparisons, paths, lifetimes, labels, strings and (escaped) char literals
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_split_2kb_head() {
+        let mut head = String::from("0");
+        while head.len() < 2048 {
+            head.push_str(
+                " + 'a: { (x < y) as u8 } + f::<Vec<&'static str>>(\"{:}\").len() + (c == '\\'') as u8 + ('\\u{3a}' >= ':') as u8",
+            );
+        }
+        let s = format!("{head}:>8");
+        let iterations = 10_000;
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            assert_eq!(split_head_spec(&s), (head.as_str(), ">8"));
+        }
+        println!(
+            "{} byte head: {:?} per split",
+            head.len(),
+            start.elapsed() / iterations
+        );
+    }
+
+    /// `count` times a field, a spaced generic call and a discarded call, each
+    /// a distinct expression
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_many_placeholders() {
+        for count in [100, 1_000, 10_000] {
+            let mut src = String::new();
+            for n in 0..count {
+                src.push_str(&format!(
+                    "{{user.items[{n}].name:>8}} {{user.get::<u8> ({n}) < limit}}{{_ = user.touch({n})}} "
+                ));
+            }
+            let lit = LitStr::new(&src, Span::call_site());
+
+            let start = Instant::now();
+            let rewritten = rewrite(&lit, []).unwrap();
+            assert_eq!(rewritten.unique, 3 * count);
+            println!(
+                "{count:>7} placeholders, {:>8} bytes: {:?}",
+                src.len(),
+                start.elapsed()
+            );
+        }
+    }
+}
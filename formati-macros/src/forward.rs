@@ -0,0 +1,97 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    Expr, Ident, Token,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+use crate::formati_args::{format_args_tokens, str_lit};
+
+/// input: path `;` [`"literal"` [`,` expr]*]
+struct ForwardInput {
+    target: syn::Path,
+    args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for ForwardInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let target = syn::Path::parse_mod_style(input)?;
+        let _: Token![;] = input.parse()?;
+        let args = Punctuated::parse_terminated(input)?;
+        Ok(Self { target, args })
+    }
+}
+
+/// Call the macro at `target` with the template rewritten, spelling the path
+/// exactly as given so it may be local, `crate::`-relative or absolute
+pub fn wrap_forward(input: TokenStream) -> TokenStream {
+    let ForwardInput { target, args } = parse_macro_input!(input as ForwardInput);
+
+    let mut args = args.into_iter();
+    let first = args.next();
+    match first.as_ref().and_then(str_lit) {
+        Some(fmt_lit) => match format_args_tokens(fmt_lit, args) {
+            Ok(args) => quote!(#target!(#args)).into(),
+            Err(err) => err.to_compile_error().into(),
+        },
+        None => {
+            let args = first.into_iter().chain(args);
+            quote!(#target!(#(#args),*)).into()
+        }
+    }
+}
+
+/// input: [attr]* name `=>` path [`;` ...]
+struct WrapMacroInput {
+    wrappers: Punctuated<WrapMacro, Token![;]>,
+}
+
+struct WrapMacro {
+    attrs: Vec<syn::Attribute>,
+    name: Ident,
+    target: syn::Path,
+}
+
+impl Parse for WrapMacroInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let wrappers = Punctuated::parse_terminated_with(input, |input| {
+            let attrs = input.call(syn::Attribute::parse_outer)?;
+            let name: Ident = input.parse()?;
+            let _: Token![=>] = input.parse()?;
+            let target = syn::Path::parse_mod_style(input)?;
+            Ok(WrapMacro {
+                attrs,
+                name,
+                target,
+            })
+        })?;
+        Ok(Self { wrappers })
+    }
+}
+
+/// Define a `macro_rules!` wrapper per `name => target` that forwards its
+/// arguments through [`wrap_forward`]
+pub fn wrap_macro(input: TokenStream) -> TokenStream {
+    let WrapMacroInput { wrappers } = parse_macro_input!(input as WrapMacroInput);
+
+    let wrappers = wrappers.into_iter().map(
+        |WrapMacro {
+             attrs,
+             name,
+             target,
+         }| {
+            quote! {
+                #(#attrs)*
+                macro_rules! #name {
+                    ($($args:tt)*) => {
+                        ::formati::__wrap_forward!(#target; $($args)*)
+                    };
+                }
+            }
+        },
+    );
+
+    quote!(#(#wrappers)*).into()
+}
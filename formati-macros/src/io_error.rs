@@ -0,0 +1,36 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Expr, Token, parse_macro_input, punctuated::Punctuated};
+
+use crate::formati_args::{format_args_tokens, str_lit};
+
+/// `kind, "template", args...` as `io::Error::new(kind, format!(...))`
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let mut args = args.into_iter();
+    let Some(kind) = args.next() else {
+        return syn::Error::new(Span::call_site(), "io_error!: expected an `ErrorKind`")
+            .to_compile_error()
+            .into();
+    };
+    let rest: Vec<Expr> = args.collect();
+
+    let Some(fmt_lit) = rest.first().and_then(str_lit).cloned() else {
+        return syn::Error::new(
+            Span::call_site(),
+            "io_error!: expected a format string after the `ErrorKind`",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    match format_args_tokens(&fmt_lit, rest.into_iter().skip(1)) {
+        Ok(tokens) => {
+            quote!(::std::io::Error::new(#kind, ::formati::__private::alloc::format!(#tokens)))
+                .into()
+        }
+        Err(err) => err.to_compile_error().into(),
+    }
+}
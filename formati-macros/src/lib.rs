@@ -0,0 +1,1802 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+
+mod bound;
+mod formati_args;
+use formati_args::{needs_alloc, wrap};
+#[cfg(any(feature = "core-macros", feature = "anyhow"))]
+use formati_args::wrap_after;
+
+/// # format
+///
+/// Format strings with enhanced dot notation and arbitrary expression support.
+///
+/// This macro extends Rust's standard `format!` macro with two key features:
+/// - Automatic handling of dot notation and arbitrary expressions for struct fields, tuple elements, method calls, and more
+/// - Deduplication of identical expressions that appear multiple times in the format string
+///
+/// ## Example
+///
+/// ```
+/// use formati::format;
+///
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// impl Point {
+///     fn distance_from_origin(&self) -> f32 {
+///         (self.x * self.x + self.y * self.y).sqrt()
+///     }
+/// }
+///
+/// let point = Point { x: 3.0, y: 4.0 };
+///
+/// // Multiple uses of point.x, point.y, and point.distance_from_origin()
+/// // will only be evaluated once each
+/// let formatted = format!(
+///     "Point: ({point.x}, {point.y})\n\
+///      Distance: {point.distance_from_origin()}\n\
+///      Normalized: ({point.x}/{point.distance_from_origin()}, {point.y}/{point.distance_from_origin()})"
+/// );
+/// ```
+///
+/// ## Format Specifiers
+///
+/// All standard format specifiers are supported, just like in `format!`:
+///
+/// ```
+/// use formati::format;
+///
+/// let point = (3.14159, 2.71828);
+/// let formatted = format!("Coordinates: ({point.0:.2}, {point.1:.3})");
+/// assert_eq!(formatted, "Coordinates: (3.14, 2.718)");
+/// ```
+///
+/// ## How It Works
+///
+/// The macro processes the format string at compile time, extracting dot notation and arbitrary expressions,
+/// deduplicating them, and transforming the format string to use standard formatting syntax.
+/// This avoids evaluating repeated expressions multiple times at runtime.
+///
+/// Deduplication is by the text of the expression, so a repeated fallible head such as
+/// `{load()?.name}` runs (and applies its `?`) once, while `{load()?.name}` and
+/// `{load()?.id}` are separate expressions that each call `load()`. Extracted
+/// expressions are evaluated in order of first appearance, after any explicitly
+/// passed arguments, and the first `?` that fails returns from the enclosing
+/// function before any later ones run.
+#[proc_macro]
+pub fn format(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => formati::__private::alloc::format);
+    needs_alloc(|| wrap(wrapped, input))
+}
+
+/// Enhanced version of format_args! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core format_args! macro, so it allocates nothing and
+/// works without `std`. It is the entry point for custom sinks: a `Log` or
+/// `Subscriber`-like type can take `fmt::Arguments` and render them with
+/// `formati::fmt_line` (or write them anywhere else). As with the core macro,
+/// the `Arguments` borrow temporaries, so pass them on in the same statement.
+///
+/// # Example
+///
+/// ```
+/// use formati::{fmt_line, format_args};
+/// use std::cell::RefCell;
+/// use std::fmt;
+///
+/// #[derive(Default)]
+/// struct Capture {
+///     lines: RefCell<Vec<String>>,
+/// }
+///
+/// impl Capture {
+///     fn log(&self, level: &str, args: fmt::Arguments<'_>) {
+///         self.lines.borrow_mut().push(format!("[{level}] {}", fmt_line(args)));
+///     }
+/// }
+///
+/// let logger = Capture::default();
+/// let user = ("alice", 3);
+/// logger.log("info", format_args!("{user.0} logged in ({user.1} sessions)"));
+/// assert_eq!(logger.lines.borrow()[0], "[info] alice logged in (3 sessions)");
+/// ```
+#[proc_macro]
+pub fn format_args(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::format_args);
+    wrap(wrapped, input)
+}
+
+mod convert;
+
+/// Enhanced version of format! that returns an `Arc<str>`
+///
+/// Formats exactly like [`format!`] and converts the resulting `String` into an
+/// `Arc<str>` once, which is handy for labels that are cached and cloned around.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_arc;
+/// use std::sync::Arc;
+///
+/// let point = (3, 4);
+/// let label: Arc<str> = format_arc!("({point.0}, {point.1})");
+/// assert_eq!(&*label, "(3, 4)");
+/// ```
+#[proc_macro]
+pub fn format_arc(input: TokenStream) -> TokenStream {
+    let target =
+        syn::parse_quote_spanned!(Span::call_site() => ::formati::__private::alloc::Arc<str>);
+    needs_alloc(|| convert::wrap_into(target, input))
+}
+
+mod io_error;
+
+/// Build a `std::io::Error` of the given kind with an interpolated message
+///
+/// Shorthand for `io::Error::new(kind, format!(...))`, with dot notation and
+/// arbitrary expression support in the message.
+///
+/// # Example
+///
+/// ```
+/// use formati::io_error;
+/// use std::io::ErrorKind;
+///
+/// let file = ("config.toml", 3);
+/// let err = io_error!(ErrorKind::NotFound, "{file.0} missing after {file.1} tries");
+///
+/// assert_eq!(err.kind(), ErrorKind::NotFound);
+/// assert_eq!(err.to_string(), "config.toml missing after 3 tries");
+/// ```
+#[proc_macro]
+pub fn io_error(input: TokenStream) -> TokenStream {
+    io_error::wrap(input)
+}
+
+/// Enhanced version of format! that returns a `Box<str>`
+///
+/// Formats exactly like [`format!`] and converts the resulting `String` with
+/// `into_boxed_str`, dropping the spare capacity and the capacity field, which
+/// suits strings stored long-term in memory-tight structs.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_boxed;
+///
+/// let point = (3, 4);
+/// let label: Box<str> = format_boxed!("({point.0}, {point.1})");
+/// assert_eq!(&*label, "(3, 4)");
+/// ```
+#[proc_macro]
+pub fn format_boxed(input: TokenStream) -> TokenStream {
+    let target =
+        syn::parse_quote_spanned!(Span::call_site() => ::formati::__private::alloc::Box<str>);
+    needs_alloc(|| convert::wrap_into(target, input))
+}
+
+mod owned;
+
+/// Enhanced version of format! that binds each extracted expression before formatting
+///
+/// [`format!`] evaluates every argument inside one `format_args!`, so temporaries
+/// such as lock guards and `RefCell` borrows live until the whole string is built.
+/// This macro instead evaluates each distinct extracted expression into its own
+/// `let` first, in template order, dropping its temporaries straight away; the
+/// caller's own arguments are still evaluated by `format!` itself.
+///
+/// A value that is a reference, such as `{name().trim()}`, is bound as its
+/// `ToOwned` form (here a `String`) so it outlives the temporary it borrows
+/// from, and places like `{user.name}` are borrowed rather than moved. Any other
+/// value that borrows from a temporary, e.g. `{path().display()}`, is rejected
+/// with E0716 where [`format!`] would accept it.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_owned;
+/// use std::cell::RefCell;
+///
+/// let log = RefCell::new(vec![1, 2]);
+///
+/// // with `format!` the first `borrow_mut()` is still held by the second and panics
+/// let s = format_owned!("{log.borrow_mut().pop().unwrap()} left: {log.borrow().len()}");
+/// assert_eq!(s, "2 left: 1");
+/// ```
+///
+/// ```compile_fail,E0716
+/// use formati::format_owned;
+/// use std::path::PathBuf;
+///
+/// // `Display<'_>` borrows the temporary `PathBuf`, which is gone by the `format!`
+/// let s = format_owned!("{PathBuf::from(\"logs\").display()}");
+/// ```
+#[proc_macro]
+pub fn format_owned(input: TokenStream) -> TokenStream {
+    needs_alloc(|| owned::wrap(input))
+}
+
+mod cow;
+
+/// Enhanced version of format! that returns a `Cow<'static, str>`
+///
+/// A template with no placeholders and no arguments expands to `Cow::Borrowed`
+/// of the literal (with `{{`/`}}` unescaped), so static messages don't allocate.
+/// Anything else is formatted like [`format!`] into `Cow::Owned`.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_cow;
+/// use std::borrow::Cow;
+///
+/// let user = ("alice", 42);
+/// assert!(matches!(format_cow!("no user"), Cow::Borrowed("no user")));
+/// assert!(matches!(format_cow!("{user.0}#{user.1}"), Cow::Owned(s) if s == "alice#42"));
+/// ```
+#[proc_macro]
+pub fn format_cow(input: TokenStream) -> TokenStream {
+    needs_alloc(|| cow::wrap(input))
+}
+
+mod doc;
+
+/// Enhanced version of format! for indented multi-line templates
+///
+/// The literal is dedented before anything is interpolated: a leading newline
+/// and the indentation shared by all non-blank lines are removed, so the template
+/// can follow the surrounding code. Rendered values, including their padding, are
+/// never touched.
+///
+/// # Example
+///
+/// ```
+/// use formati::formatdoc;
+///
+/// let row = ("cpu", 93.5);
+/// let report = formatdoc!("
+///     name  {row.0:>6}
+///       use {row.1:>6.1}%
+/// ");
+/// assert_eq!(report, "name     cpu\n  use   93.5%\n");
+/// ```
+#[proc_macro]
+pub fn formatdoc(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => formati::__private::alloc::format);
+    needs_alloc(|| doc::wrap(wrapped, input))
+}
+
+/// Enhanced version of format! that uppercases the whole result
+///
+/// The transform applies to the formatted `String`, so literal text is
+/// uppercased along with the interpolated values.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_upper;
+///
+/// let rec = (7, "draft");
+/// assert_eq!(format_upper!("id-{rec.0}: {rec.1}"), "ID-7: DRAFT");
+/// ```
+#[proc_macro]
+pub fn format_upper(input: TokenStream) -> TokenStream {
+    let transform = syn::parse_quote_spanned!(Span::call_site() => __formati_s.to_uppercase());
+    needs_alloc(|| convert::wrap_transform(transform, input))
+}
+
+/// Enhanced version of format! that lowercases the whole result
+///
+/// The transform applies to the formatted `String`, so literal text is
+/// lowercased along with the interpolated values.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_lower;
+///
+/// let rec = (7, "DRAFT");
+/// assert_eq!(format_lower!("ID-{rec.0}: {rec.1}"), "id-7: draft");
+/// ```
+#[proc_macro]
+pub fn format_lower(input: TokenStream) -> TokenStream {
+    let transform = syn::parse_quote_spanned!(Span::call_site() => __formati_s.to_lowercase());
+    needs_alloc(|| convert::wrap_transform(transform, input))
+}
+
+/// Enhanced version of format! that trims leading and trailing whitespace from
+/// the whole result
+///
+/// # Example
+///
+/// ```
+/// use formati::format_trim;
+///
+/// let rec = (7, " draft\n");
+/// assert_eq!(format_trim!("  {rec.0}:{rec.1}"), "7: draft");
+/// ```
+#[proc_macro]
+pub fn format_trim(input: TokenStream) -> TokenStream {
+    let transform = syn::parse_quote_spanned!(Span::call_site() =>
+        ::formati::__private::alloc::String::from(__formati_s.trim())
+    );
+    needs_alloc(|| convert::wrap_transform(transform, input))
+}
+
+mod escape;
+
+/// Enhanced version of format! that HTML-escapes every interpolated value
+///
+/// `<`, `>`, `&`, `"` and `'` in the rendered values become character
+/// references; the template's own text is not touched, so it can hold markup.
+/// Specs apply before escaping. Besides inline expressions only `name = expr`
+/// arguments are accepted.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_html;
+///
+/// let user = ("<script>", 3);
+/// assert_eq!(
+///     format_html!("<b>{user.0}</b> ({user.1:>2})"),
+///     "<b>&lt;script&gt;</b> ( 3)"
+/// );
+/// ```
+#[proc_macro]
+pub fn format_html(input: TokenStream) -> TokenStream {
+    let escape =
+        syn::parse_quote_spanned!(Span::call_site() => ::formati::__private::Escaped::html);
+    needs_alloc(|| escape::wrap(escape, input))
+}
+
+/// Enhanced version of format! that shell-quotes every interpolated value
+///
+/// Each rendered value is spliced in as a single POSIX shell word: left bare
+/// when it only holds characters that are safe unquoted, single-quoted
+/// otherwise. The template's own text is not touched. Specs apply before
+/// quoting. Besides inline expressions only `name = expr` arguments are
+/// accepted.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_shell;
+///
+/// let file = ("notes.txt", "my notes; rm -rf ~");
+/// assert_eq!(format_shell!("cat {file.0}"), "cat notes.txt");
+/// assert_eq!(format_shell!("cat {file.1}"), "cat 'my notes; rm -rf ~'");
+/// ```
+#[proc_macro]
+pub fn format_shell(input: TokenStream) -> TokenStream {
+    let escape =
+        syn::parse_quote_spanned!(Span::call_site() => ::formati::__private::Escaped::shell);
+    needs_alloc(|| escape::wrap(escape, input))
+}
+
+mod trunc;
+
+/// Enhanced version of format! that caps the result at a number of chars
+///
+/// `format_trunc!(max, ellipsis, "template", args...)` formats like [`format!`];
+/// a result longer than `max` chars is cut on a char boundary and `ellipsis` is
+/// appended, with the ellipsis counting towards `max`. Lengths are in chars,
+/// not bytes, so multibyte characters are never split.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_trunc;
+///
+/// let req = ("GET", "/api/v1/users/12345/preferences");
+/// assert_eq!(format_trunc!(20, "…", "{req.0} {req.1}"), "GET /api/v1/users/1…");
+/// assert_eq!(format_trunc!(20, "…", "{req.0} /"), "GET /");
+/// ```
+#[proc_macro]
+pub fn format_trunc(input: TokenStream) -> TokenStream {
+    needs_alloc(|| trunc::wrap(input))
+}
+
+mod try_format;
+
+/// Fallible version of format! returning `Result<String, FormatError>`
+///
+/// A placeholder whose value implements `formati::TryDisplay` is rendered with
+/// it instead of `Display`, and the first one that fails makes the whole call
+/// return its `FormatError`; every other value formats as usual, specs
+/// included. Only `{}` goes through `TryDisplay`: `{v:?}`, `{v:x}` and the
+/// other traits use the value's own impls. Besides inline expressions only
+/// `name = expr` arguments are accepted.
+///
+/// # Example
+///
+/// ```
+/// use formati::{FormatError, TryDisplay, try_format};
+/// use std::fmt;
+///
+/// struct Port(u32);
+///
+/// impl TryDisplay for Port {
+///     fn try_fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), FormatError> {
+///         if self.0 > 65535 {
+///             return Err(FormatError::new(format_args!("port {} out of range", self.0)));
+///         }
+///         write!(f, "{}", self.0)?;
+///         Ok(())
+///     }
+/// }
+///
+/// let server = ("example.com", Port(8080), Port(70000));
+/// assert_eq!(try_format!("{server.0}:{server.1}").unwrap(), "example.com:8080");
+/// assert_eq!(
+///     try_format!("{server.0}:{server.2}").unwrap_err().message(),
+///     "port 70000 out of range"
+/// );
+/// ```
+#[proc_macro]
+pub fn try_format(input: TokenStream) -> TokenStream {
+    needs_alloc(|| try_format::wrap(input))
+}
+
+mod sep;
+
+/// Format a list of values with `Display`, joined by a separator
+///
+/// `format_sep!(sep, values...)` takes the separator as a string literal and
+/// then any expressions, rather than a template. No values give an empty
+/// `String`, and a single value has no separator.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_sep;
+///
+/// let user = ("alice", 30, "admin");
+/// assert_eq!(format_sep!(" | ", user.0, user.1, user.2), "alice | 30 | admin");
+/// assert_eq!(format_sep!(", ", user.0), "alice");
+/// ```
+#[proc_macro]
+pub fn format_sep(input: TokenStream) -> TokenStream {
+    needs_alloc(|| sep::wrap(input))
+}
+
+mod min;
+
+/// Restricted version of format! that only accepts plain `{expr}` placeholders
+///
+/// Dot notation and arbitrary expressions work as in [`format!`], but any format
+/// spec (`{x:?}`, `{x:>8}`, `{:.2}`, ...) is rejected at compile time, so every
+/// value goes through `Display` with default formatting.
+///
+/// # Example
+///
+/// ```
+/// use formati::uformat_min;
+///
+/// let reading = (7, 21.5);
+/// assert_eq!(uformat_min!("sensor {reading.0}: {reading.1}"), "sensor 7: 21.5");
+/// ```
+///
+/// ```compile_fail
+/// use formati::uformat_min;
+///
+/// let reading = (7, 21.5);
+/// let _ = uformat_min!("sensor {reading.0}: {reading.1:.1}");
+/// ```
+#[proc_macro]
+pub fn uformat_min(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => formati::__private::alloc::format);
+    needs_alloc(|| min::wrap(wrapped, input))
+}
+
+mod audit;
+
+/// Enhanced version of format! that also reports how the template was deduplicated
+///
+/// Returns `(String, formati::FormatAudit)`. The audit is computed at compile time
+/// and counts the placeholders holding extracted expressions (repeats included)
+/// and the distinct expressions actually evaluated, which is handy for asserting
+/// in tests that a template dedups as expected.
+///
+/// # Example
+///
+/// ```
+/// use formati::format_audit;
+///
+/// let point = (3, 4);
+/// let (s, audit) = format_audit!("({point.0}, {point.1}) x={point.0}");
+///
+/// assert_eq!(s, "(3, 4) x=3");
+/// assert_eq!(audit.placeholders(), 3);
+/// assert_eq!(audit.unique_expressions(), 2);
+/// assert_eq!(audit.saved_evaluations(), 1);
+/// ```
+#[proc_macro]
+pub fn format_audit(input: TokenStream) -> TokenStream {
+    needs_alloc(|| audit::wrap(input))
+}
+
+mod const_format;
+
+/// Build a `&'static str` from a template whose placeholders are all literals
+///
+/// Expands to `concat!`, so the result can initialise a `const` or `static`.
+/// Each placeholder must hold a string, char, integer or bool literal
+/// (e.g. `{404}` or `{"not found"}`); variables, expressions and format specs
+/// are compile errors since they can't be evaluated at compile time. Float
+/// literals are rejected too: `concat!` copies them as written, so `{1e3}` would
+/// not read `1000` like `format!` renders it.
+///
+/// # Example
+///
+/// ```
+/// use formati::const_format;
+///
+/// const NOT_FOUND: &str = const_format!("E{404}: {\"not found\"} (retry={false})");
+/// assert_eq!(NOT_FOUND, "E404: not found (retry=false)");
+/// ```
+///
+/// ```compile_fail
+/// use formati::const_format;
+///
+/// const CODE: u16 = 404;
+/// const MSG: &str = const_format!("E{CODE}");
+/// ```
+#[proc_macro]
+pub fn const_format(input: TokenStream) -> TokenStream {
+    const_format::wrap(input)
+}
+
+mod template;
+
+/// Define a named template that [`format_const!`] can format
+///
+/// Expands to a `const NAME: &str` holding the literal, plus a `macro_rules!`
+/// of the same name through which `format_const!` gets at the literal itself
+/// (a proc macro can't read a `const`'s value). Like any `macro_rules!`, the
+/// template is only visible after its definition.
+///
+/// # Example
+///
+/// ```
+/// use formati::{format_const, template};
+///
+/// template!(GREETING = "Hello {user.0} (#{user.1})");
+///
+/// let user = ("alice", 7);
+/// assert_eq!(format_const!(GREETING), "Hello alice (#7)");
+/// assert_eq!(GREETING, "Hello {user.0} (#{user.1})");
+/// ```
+#[proc_macro]
+pub fn template(input: TokenStream) -> TokenStream {
+    template::define(input)
+}
+
+/// Enhanced version of format! taking a template defined with [`template!`]
+///
+/// The template is formatted exactly like the same literal passed to [`format!`]
+/// at the call site, so its placeholders see the variables in scope here, and
+/// further arguments can follow the name. A plain `const`, a `concat!` or any
+/// other expression is rejected: only a `template!` is known while macros expand.
+///
+/// # Example
+///
+/// ```
+/// use formati::{format_const, template};
+///
+/// template!(ROW = "{name:<6}|{} {scores.iter().sum::<u32>():>4}");
+///
+/// let name = "bob";
+/// let scores = [12, 30];
+/// assert_eq!(format_const!(ROW, scores.len()), "bob   |2   42");
+/// ```
+#[proc_macro]
+pub fn format_const(input: TokenStream) -> TokenStream {
+    template::format_const(input)
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __format_template(input: TokenStream) -> TokenStream {
+    template::expand(input)
+}
+
+mod vformat;
+
+/// Format a template against a table of values resolved at runtime
+///
+/// Unlike [`format!`], the placeholders are not expressions: each `{name}` (optionally
+/// with a format spec, e.g. `{name:>8}`) is looked up by name in the table when the
+/// macro runs. The table can be anything implementing `formati::ValueTable`, such as a
+/// slice of `(&str, &dyn Display)` pairs or a `HashMap<&str, V>`.
+///
+/// Returns `Result<String, formati::MissingValue>`, failing if any name has no entry.
+///
+/// # Example
+///
+/// ```
+/// use formati::vformat;
+/// use std::fmt::Display;
+///
+/// let user = (String::from("Alice"), 42);
+/// let table: [(&str, &dyn Display); 2] = [("name", &user.0), ("id", &user.1)];
+///
+/// let s = vformat!(table, "User {name} has ID {id:>4}").unwrap();
+/// assert_eq!(s, "User Alice has ID   42");
+///
+/// let err = vformat!(table, "User {email}").unwrap_err();
+/// assert_eq!(err.name(), "email");
+/// ```
+#[proc_macro]
+pub fn vformat(input: TokenStream) -> TokenStream {
+    needs_alloc(|| vformat::wrap(input))
+}
+
+mod write;
+
+/// Enhanced version of write! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core write! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+/// The destination is passed through untouched, so it may itself use dot notation,
+/// and needs no `use` of `fmt::Write`. For an `io::Write` destination, use
+/// [`io_write!`] or import `std::io::Write`.
+///
+/// # Example
+///
+/// ```
+/// use formati::write;
+///
+/// struct Record {
+///     id: u32,
+/// }
+///
+/// struct Context {
+///     out: String,
+/// }
+///
+/// let record = Record { id: 7 };
+/// let mut ctx = Context { out: String::new() };
+///
+/// write!(ctx.out, "Record #{record.id}").unwrap();
+/// assert_eq!(ctx.out, "Record #7");
+/// ```
+#[proc_macro]
+pub fn write(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::write);
+    write::wrap_write(wrapped, input)
+}
+
+/// Enhanced version of writeln! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core writeln! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+/// The destination is passed through untouched, so it may itself use dot notation,
+/// and needs no `use` of `fmt::Write`. For an `io::Write` destination, use
+/// [`io_writeln!`] or import `std::io::Write`.
+///
+/// # Example
+///
+/// ```
+/// use formati::writeln;
+///
+/// let record = (7, "created");
+/// let mut out = String::new();
+///
+/// writeln!(out, "Record #{record.0}: {record.1}").unwrap();
+/// assert_eq!(out, "Record #7: created\n");
+/// ```
+#[proc_macro]
+pub fn writeln(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::writeln);
+    write::wrap_write(wrapped, input)
+}
+
+/// writeln! into any `core::fmt::Write` sink, with dot notation and arbitrary expression support
+///
+/// Unlike [`writeln!`], which calls whatever `write_fmt` the destination has, this
+/// only ever uses `core::fmt::Write`, so it works the same for `String` and custom
+/// collectors and returns `core::fmt::Result` even for a type that is also
+/// `io::Write`.
+///
+/// # Example
+///
+/// ```
+/// use formati::fmt_writeln;
+///
+/// let record = (7, "created");
+/// let mut out = String::new();
+///
+/// fmt_writeln!(out, "Record #{record.0}: {record.1}").unwrap();
+/// assert_eq!(out, "Record #7: created\n");
+/// ```
+#[proc_macro]
+pub fn fmt_writeln(input: TokenStream) -> TokenStream {
+    write::wrap_fmt_writeln(input)
+}
+
+/// write! into any `std::io::Write` sink, with dot notation and arbitrary expression support
+///
+/// Only ever uses `std::io::Write`, so it needs no `use` and returns
+/// `io::Result<()>` even for a type that is also `fmt::Write`, which
+/// [`write!`] would resolve to the `fmt` side.
+///
+/// # Example
+///
+/// ```
+/// use formati::io_write;
+///
+/// let record = (7, "created");
+/// let mut buf: Vec<u8> = Vec::new();
+///
+/// io_write!(buf, "Record #{record.0}: {record.1}").unwrap();
+/// assert_eq!(buf, b"Record #7: created");
+/// ```
+#[proc_macro]
+pub fn io_write(input: TokenStream) -> TokenStream {
+    write::wrap_io_write(input)
+}
+
+/// writeln! into any `std::io::Write` sink, with dot notation and arbitrary expression support
+///
+/// The line-ending counterpart of [`io_write!`].
+///
+/// # Example
+///
+/// ```
+/// use formati::io_writeln;
+///
+/// let record = (7, "created");
+/// let mut buf: Vec<u8> = Vec::new();
+///
+/// io_writeln!(buf, "Record #{record.0}: {record.1}").unwrap();
+/// assert_eq!(buf, b"Record #7: created\n");
+/// ```
+#[proc_macro]
+pub fn io_writeln(input: TokenStream) -> TokenStream {
+    write::wrap_io_writeln(input)
+}
+
+/// Append to a `String` with dot notation and arbitrary expression support,
+/// returning the number of bytes appended
+///
+/// Evaluates to a `usize`, the growth of the string's length, which is handy
+/// for length-prefixed framing where a header depends on the body size.
+///
+/// # Example
+///
+/// ```
+/// use formati::write_len;
+///
+/// let frame = ("ping", 7);
+/// let mut buf = String::from("HDR ");
+///
+/// let body_len = write_len!(buf, "{frame.0}:{frame.1}");
+/// assert_eq!(body_len, 6);
+/// assert_eq!(buf, "HDR ping:7");
+/// ```
+#[proc_macro]
+pub fn write_len(input: TokenStream) -> TokenStream {
+    write::wrap_write_len(input)
+}
+
+mod forward;
+
+/// Define wrappers that give other macros dot notation and arbitrary expression support
+///
+/// Each `name => path` entry defines a `macro_rules!` macro `name` that rewrites
+/// its template like [`format!`] does and passes the result on to the macro at
+/// `path`, e.g. a logging macro from another crate or one of your own. Entries
+/// are separated by `;` and may carry attributes such as `#[macro_export]`.
+///
+/// The target must take std `format!`-style arguments: the template literal
+/// first, followed by positional and `name = value` arguments. Calls whose first
+/// argument isn't a string literal are forwarded unchanged.
+///
+/// # Example
+///
+/// ```
+/// macro_rules! shout {
+///     ($($arg:tt)*) => { format!($($arg)*).to_uppercase() };
+/// }
+///
+/// formati::wrap_macro!(shout_f => shout);
+///
+/// let user = ("alice", 42);
+/// assert_eq!(shout_f!("{user.0} is {user.1}"), "ALICE IS 42");
+/// ```
+#[proc_macro]
+pub fn wrap_macro(input: TokenStream) -> TokenStream {
+    forward::wrap_macro(input)
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __wrap_forward(input: TokenStream) -> TokenStream {
+    forward::wrap_forward(input)
+}
+
+mod debug_struct;
+
+/// Build a `Debug` impl's output from `name = value` fields
+///
+/// Expands to the `f.debug_struct(name).field("x", &value)....finish()` chain, so it
+/// honours `{:#?}` like a hand-written impl. A string literal value is treated as a
+/// template with dot notation and arbitrary expression support, and is shown as
+/// the formatted text rather than a quoted string.
+///
+/// # Example
+///
+/// ```
+/// use formati::debug_struct;
+/// use std::fmt;
+///
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// impl fmt::Debug for Point {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         debug_struct!(f, "Point", x = self.x, y = self.y, sum = "{self.x + self.y}")
+///     }
+/// }
+///
+/// assert_eq!(format!("{:?}", Point { x: 1, y: 2 }), "Point { x: 1, y: 2, sum: 3 }");
+/// ```
+#[proc_macro]
+pub fn debug_struct(input: TokenStream) -> TokenStream {
+    debug_struct::wrap(input)
+}
+
+mod debug_unwrap;
+
+/// Unwrap a `Result` or panic with an interpolated context message
+///
+/// Takes the `Result` followed by a template with dot notation and arbitrary
+/// expression support. On `Err` it panics with `"<context>: <error:?>"`; the
+/// template is only formatted on that path and may refer to the error as `err`.
+///
+/// # Example
+///
+/// ```
+/// use formati::debug_unwrap;
+///
+/// let case = ("parse_port", "8080");
+/// let port: u16 = debug_unwrap!(case.1.parse::<u16>(), "case {case.0}");
+/// assert_eq!(port, 8080);
+/// ```
+///
+/// ```should_panic
+/// use formati::debug_unwrap;
+///
+/// let case = ("parse_port", "80a");
+/// // case parse_port (err: invalid digit found in string): ParseIntError { kind: InvalidDigit }
+/// let _: u16 = debug_unwrap!(case.1.parse::<u16>(), "case {case.0} (err: {err})");
+/// ```
+#[proc_macro]
+pub fn debug_unwrap(input: TokenStream) -> TokenStream {
+    debug_unwrap::wrap(input)
+}
+
+/// Enhanced version of print! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard print! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+/// The rewritten template and arguments go straight to `std::print!`, so
+/// nothing is formatted into an intermediate `String`.
+///
+/// # Example
+///
+/// ```
+/// use formati::print;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// print!("User {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn print(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => std::print);
+    wrap(wrapped, input)
+}
+
+/// Enhanced version of println! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard println! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::println;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// println!("User {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn println(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => std::println);
+    wrap(wrapped, input)
+}
+
+/// Enhanced version of eprint! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard eprint! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::eprint;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// eprint!("Error: Failed to process user {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn eprint(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => std::eprint);
+    wrap(wrapped, input)
+}
+
+/// Enhanced version of eprintln! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard eprintln! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::eprintln;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// eprintln!("Error: Failed to process user {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn eprintln(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => std::eprintln);
+    wrap(wrapped, input)
+}
+
+#[cfg(feature = "stdio")]
+mod stdio;
+
+/// Like `print!` but also returns the formatted `String`
+///
+/// The message is formatted once and the same `String` is both printed with
+/// the standard print! macro and returned, so extracted expressions are only
+/// evaluated once.
+///
+/// # Example
+///
+/// ```
+/// use formati::print_s;
+///
+/// let user = (String::from("Alice"), 42);
+///
+/// let s = print_s!("User {user.0} with ID {user.1}");
+/// assert_eq!(s, "User Alice with ID 42");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn print_s(input: TokenStream) -> TokenStream {
+    let sink = syn::parse_quote_spanned!(Span::call_site() => std::print);
+    stdio::wrap_returning(sink, input)
+}
+
+/// Like `println!` but also returns the formatted `String`
+///
+/// The message is formatted once and the same `String` is both printed with
+/// the standard println! macro and returned, so extracted expressions are only
+/// evaluated once.
+///
+/// # Example
+///
+/// ```
+/// use formati::println_s;
+///
+/// let user = (String::from("Alice"), 42);
+///
+/// let s = println_s!("User {user.0} with ID {user.1}");
+/// assert_eq!(s, "User Alice with ID 42");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn println_s(input: TokenStream) -> TokenStream {
+    let sink = syn::parse_quote_spanned!(Span::call_site() => std::println);
+    stdio::wrap_returning(sink, input)
+}
+
+/// Like `eprint!` but also returns the formatted `String`
+///
+/// The message is formatted once and the same `String` is both printed with
+/// the standard eprint! macro and returned, so extracted expressions are only
+/// evaluated once.
+///
+/// # Example
+///
+/// ```
+/// use formati::eprint_s;
+///
+/// let user = (String::from("Alice"), 42);
+///
+/// let s = eprint_s!("User {user.0} with ID {user.1}");
+/// assert_eq!(s, "User Alice with ID 42");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn eprint_s(input: TokenStream) -> TokenStream {
+    let sink = syn::parse_quote_spanned!(Span::call_site() => std::eprint);
+    stdio::wrap_returning(sink, input)
+}
+
+/// Like `eprintln!` but also returns the formatted `String`
+///
+/// The message is formatted once and the same `String` is both printed with
+/// the standard eprintln! macro and returned, so extracted expressions are only
+/// evaluated once.
+///
+/// # Example
+///
+/// ```
+/// use formati::eprintln_s;
+///
+/// let user = (String::from("Alice"), 42);
+///
+/// let s = eprintln_s!("User {user.0} with ID {user.1}");
+/// assert_eq!(s, "User Alice with ID 42");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn eprintln_s(input: TokenStream) -> TokenStream {
+    let sink = syn::parse_quote_spanned!(Span::call_site() => std::eprintln);
+    stdio::wrap_returning(sink, input)
+}
+
+/// Enhanced version of dbg! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard dbg! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+/// A leading string literal is passed on as is, followed by each distinct
+/// expression from its placeholders, so each is printed with its value and the
+/// whole call returns them as a tuple, like `std::dbg!` with several arguments.
+///
+/// # Example
+///
+/// ```
+/// use formati::dbg;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// dbg!("Debug: user {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn dbg(input: TokenStream) -> TokenStream {
+    stdio::wrap_dbg(input)
+}
+
+/// Print a labelled value to stderr and return it, for inspecting a chain inline
+///
+/// `tee!(expr, "label", args...)` evaluates `expr` once, prints the label
+/// (formatted with dot notation and arbitrary expression support) followed by
+/// `expr`'s `Debug` as `label: value`, and returns `expr`. Without a label only
+/// the value is printed.
+///
+/// # Example
+///
+/// ```
+/// use formati::tee;
+///
+/// let order = (17, vec![3, 4]);
+///
+/// // prints "order 17 total: 7"
+/// let total = tee!(order.1.iter().sum::<u32>(), "order {order.0} total") * 2;
+/// assert_eq!(total, 14);
+/// ```
+#[proc_macro]
+#[cfg(feature = "stdio")]
+pub fn tee(input: TokenStream) -> TokenStream {
+    stdio::wrap_tee(input)
+}
+
+/// Enhanced version of panic! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core panic! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```should_panic
+/// use formati::panic;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// panic!("Critical error: user {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn panic(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::panic);
+    wrap_after(wrapped, 0, input)
+}
+
+/// Enhanced version of unreachable! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core unreachable! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::unreachable;
+///
+/// let state = (3, "ready");
+///
+/// let label = match state.0 {
+///     0..=9 => "single digit",
+///     _ => unreachable!("state {state.1} has invalid code {state.0}"),
+/// };
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn unreachable(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::unreachable);
+    wrap_after(wrapped, 0, input)
+}
+
+/// Enhanced version of todo! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core todo! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::todo;
+///
+/// struct Request {
+///     method: String,
+/// }
+///
+/// fn handle(request: &Request) -> u16 {
+///     todo!("support {request.method} requests")
+/// }
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn todo(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::todo);
+    wrap_after(wrapped, 0, input)
+}
+
+/// Enhanced version of unimplemented! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core unimplemented! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::unimplemented;
+///
+/// struct Codec {
+///     name: String,
+/// }
+///
+/// fn decode(codec: &Codec, _bytes: &[u8]) -> Vec<u8> {
+///     unimplemented!("decoding for {codec.name}")
+/// }
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn unimplemented(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::unimplemented);
+    wrap_after(wrapped, 0, input)
+}
+
+#[cfg(feature = "core-macros")]
+mod assertions;
+
+/// Enhanced version of assert! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core assert! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication
+/// in the optional message.
+///
+/// The message, and every expression in it, is only evaluated when the
+/// assertion fails.
+///
+/// Without a message, a comparison condition also reports both operands, e.g.
+/// `assertion failed: user.1 >= 18 (left = 17, right = 18)`. An operand whose
+/// type has no `Debug` impl is shown as `_`.
+///
+/// # Example
+///
+/// ```
+/// use formati::assert;
+///
+/// let user = (String::from("Alice"), 30);
+///
+/// assert!(user.1 >= 18, "user {user.0} is only {user.1}");
+/// assert!(user.1 >= 18);
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn assert(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::assert);
+    assertions::wrap(wrapped, input)
+}
+
+/// Enhanced version of assert_eq! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core assert_eq! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication
+/// in the optional message.
+///
+/// # Example
+///
+/// ```
+/// use formati::assert_eq;
+///
+/// let user = (String::from("Alice"), 30);
+///
+/// assert_eq!(user.0.len(), 5, "unexpected name length for {user.0}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn assert_eq(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::assert_eq);
+    wrap_after(wrapped, 2, input)
+}
+
+/// Enhanced version of assert_ne! with dot notation and arbitrary expression support
+///
+/// This macro wraps the core assert_ne! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication
+/// in the optional message.
+///
+/// # Example
+///
+/// ```
+/// use formati::assert_ne;
+///
+/// let user = (String::from("Alice"), 30);
+///
+/// assert_ne!(user.1, 0, "user {user.0} has no age");
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn assert_ne(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => core::assert_ne);
+    wrap_after(wrapped, 2, input)
+}
+
+/// Enhanced version of debug_assert! with dot notation and arbitrary expression support
+///
+/// Expands like [`assert!`] under `#[cfg(debug_assertions)]`, so in release builds
+/// neither the condition nor any expression in the message is compiled. Unlike
+/// std's `debug_assert!`, a variable only the assertion uses is then unused.
+///
+/// # Example
+///
+/// ```
+/// use formati::debug_assert;
+///
+/// let user = ("Alice", 30);
+///
+/// debug_assert!(user.1 >= 18, "{user.0} is only {user.1}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn debug_assert(input: TokenStream) -> TokenStream {
+    assertions::debug_only(assert(input))
+}
+
+/// Enhanced version of debug_assert_eq! with dot notation and arbitrary expression support
+///
+/// Expands like [`assert_eq!`] under `#[cfg(debug_assertions)]`, so in release
+/// builds neither the operands nor any expression in the message are compiled.
+///
+/// # Example
+///
+/// ```
+/// use formati::debug_assert_eq;
+///
+/// let user = (String::from("Alice"), 30);
+///
+/// debug_assert_eq!(user.0.len(), 5, "unexpected name length for {user.0}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn debug_assert_eq(input: TokenStream) -> TokenStream {
+    assertions::debug_only(assert_eq(input))
+}
+
+/// Enhanced version of debug_assert_ne! with dot notation and arbitrary expression support
+///
+/// Expands like [`assert_ne!`] under `#[cfg(debug_assertions)]`, so in release
+/// builds neither the operands nor any expression in the message are compiled.
+///
+/// # Example
+///
+/// ```
+/// use formati::debug_assert_ne;
+///
+/// let user = (String::from("Alice"), 30);
+///
+/// debug_assert_ne!(user.1, 0, "user {user.0} has no age");
+/// ```
+#[proc_macro]
+#[cfg(feature = "core-macros")]
+pub fn debug_assert_ne(input: TokenStream) -> TokenStream {
+    assertions::debug_only(assert_ne(input))
+}
+
+/// Enhanced version of anyhow! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard anyhow! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::anyhow;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// let err = anyhow!("Failed to process user {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(feature = "anyhow")]
+pub fn anyhow(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => anyhow::anyhow);
+    wrap(wrapped, input)
+}
+
+/// Enhanced version of bail! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard bail! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::bail;
+/// use anyhow::Result;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// fn process_user(user: &User) -> Result<()> {
+///     if user.id == 0 {
+///         bail!("Invalid user {user.name} with ID {user.id}");
+///     }
+///     Ok(())
+/// }
+/// ```
+#[proc_macro]
+#[cfg(feature = "anyhow")]
+pub fn bail(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => anyhow::bail);
+    wrap(wrapped, input)
+}
+
+/// Enhanced version of anyhow's ensure! with dot notation and arbitrary
+/// expression support in the message
+///
+/// The message, and every expression in it, is only evaluated when the
+/// condition is false.
+///
+/// # Example
+///
+/// ```
+/// use formati::ensure;
+/// use anyhow::Result;
+///
+/// fn withdraw(account: (&str, u32), amount: u32) -> Result<u32> {
+///     ensure!(account.1 >= amount, "{account.0} has only {account.1}, needs {amount}");
+///     Ok(account.1 - amount)
+/// }
+///
+/// assert_eq!(withdraw(("alice", 30), 10).unwrap(), 20);
+/// assert_eq!(
+///     withdraw(("bob", 5), 10).unwrap_err().to_string(),
+///     "bob has only 5, needs 10"
+/// );
+/// ```
+#[proc_macro]
+#[cfg(feature = "anyhow")]
+pub fn ensure(input: TokenStream) -> TokenStream {
+    let wrapped = syn::parse_quote_spanned!(Span::call_site() => anyhow::ensure);
+    wrap_after(wrapped, 1, input)
+}
+
+#[cfg(feature = "anyhow")]
+mod bail_at;
+
+/// Like [`bail!`], with the call site's location attached as context
+///
+/// The interpolated message becomes the root of the error chain and
+/// `file:line` of the invocation is added on top of it, so `{:#}` prints both.
+///
+/// # Example
+///
+/// ```
+/// use formati::bail_at;
+/// use anyhow::Result;
+///
+/// fn load(path: &str) -> Result<()> {
+///     bail_at!("cannot open {path} ({path.len()} bytes of path)");
+/// }
+///
+/// let err = load("a.toml").unwrap_err();
+/// assert_eq!(err.root_cause().to_string(), "cannot open a.toml (6 bytes of path)");
+/// assert!(err.to_string().contains(".rs:"));
+/// ```
+#[proc_macro]
+#[cfg(feature = "anyhow")]
+pub fn bail_at(input: TokenStream) -> TokenStream {
+    bail_at::wrap(input)
+}
+
+/// Interpolate a template into a `serde_json::Value::String`
+///
+/// Formats like [`format!`], with dot notation and arbitrary expression support,
+/// and wraps the result as a JSON string value, e.g. for fields of structured logs.
+///
+/// # Example
+///
+/// ```
+/// use formati::json_str;
+/// use serde_json::{Value, json};
+///
+/// let rec = (7, "insert");
+/// let id: Value = json_str!("{rec.0}-{rec.1}");
+///
+/// assert_eq!(json!({ "id": id }), json!({ "id": "7-insert" }));
+/// ```
+#[proc_macro]
+#[cfg(feature = "serde_json")]
+pub fn json_str(input: TokenStream) -> TokenStream {
+    let target = syn::parse_quote_spanned!(Span::call_site() => ::serde_json::Value);
+    convert::wrap_into(target, input)
+}
+
+#[cfg(feature = "tracing")]
+mod like_tracing;
+
+#[cfg(feature = "log-kv")]
+mod like_log;
+
+/// Enhanced version of log!'s structured key-value form with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard log! macro, forwarding `key = value` fields to
+/// `log`'s key-value API and rewriting the message with support for dot notation
+/// and arbitrary expressions with automatic expression deduplication.
+///
+/// Fields come after the level and before the message literal; an optional
+/// `target: "..."` may come first, just like `log!`.
+///
+/// # Example
+///
+/// ```
+/// use formati::kv;
+/// use log::Level;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// kv!(target: "auth", Level::Info, user_id = user.id, "User {user.name} logged in");
+/// ```
+#[proc_macro]
+#[cfg(feature = "log-kv")]
+pub fn kv(input: TokenStream) -> TokenStream {
+    like_log::wrap_kv(input)
+}
+
+/// Log a value at trace level and return it, like `#[instrument(ret)]` for an
+/// expression
+///
+/// `trace_ret!(expr, "message", args...)` evaluates `expr` once, emits a
+/// `tracing` trace event whose message is formatted with dot notation and
+/// arbitrary expression support and whose `return` field holds `expr`'s
+/// `Debug`, and returns `expr`. The message is optional.
+///
+/// # Example
+///
+/// ```
+/// use formati::trace_ret;
+///
+/// fn total(order: &(u32, Vec<u32>)) -> u32 {
+///     // emits "order 17 total return=7"
+///     trace_ret!(order.1.iter().sum(), "order {order.0} total")
+/// }
+///
+/// assert_eq!(total(&(17, vec![3, 4])), 7);
+/// ```
+#[proc_macro]
+#[cfg(feature = "tracing")]
+pub fn trace_ret(input: TokenStream) -> TokenStream {
+    like_tracing::wrap_ret(input)
+}
+
+/// Enhanced version of trace! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard trace! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::trace;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// trace!("Entering function with user {user.name} and ID {user.id}");
+/// ```
+///
+/// With the `log-kv` feature, a `target: "..."` and `key = value` fields may
+/// precede the message, as with `kv!`, e.g.
+/// `trace!(target: "db", query_id = query.id, "ran {query.name}")`.
+#[proc_macro]
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub fn trace(input: TokenStream) -> TokenStream {
+    #[cfg(all(feature = "log", not(feature = "log-kv")))]
+    {
+        let wrapped = syn::parse_quote_spanned!(Span::call_site() => log::trace);
+        wrap(wrapped, input)
+    }
+    #[cfg(feature = "log-kv")]
+    {
+        like_log::wrap_level("Trace", input)
+    }
+    #[cfg(feature = "tracing")]
+    {
+        like_tracing::wrap("trace", input)
+    }
+}
+
+/// Enhanced version of debug! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard debug! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::debug;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// debug!("Debug user object state: name={user.name}, id={user.id}");
+/// ```
+#[proc_macro]
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub fn debug(input: TokenStream) -> TokenStream {
+    #[cfg(all(feature = "log", not(feature = "log-kv")))]
+    {
+        let wrapped = syn::parse_quote_spanned!(Span::call_site() => log::debug);
+        wrap(wrapped, input)
+    }
+    #[cfg(feature = "log-kv")]
+    {
+        like_log::wrap_level("Debug", input)
+    }
+    #[cfg(feature = "tracing")]
+    {
+        like_tracing::wrap("debug", input)
+    }
+}
+
+/// Enhanced version of info! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard info! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::info;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// info!("Processing user {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub fn info(input: TokenStream) -> TokenStream {
+    #[cfg(all(feature = "log", not(feature = "log-kv")))]
+    {
+        let wrapped = syn::parse_quote_spanned!(Span::call_site() => log::info);
+        wrap(wrapped, input)
+    }
+    #[cfg(feature = "log-kv")]
+    {
+        like_log::wrap_level("Info", input)
+    }
+    #[cfg(feature = "tracing")]
+    {
+        like_tracing::wrap("info", input)
+    }
+}
+
+/// Enhanced version of warn! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard warn! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::warn;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// warn!("Warning: user {user.name} has suspicious activity");
+/// ```
+#[proc_macro]
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub fn warn(input: TokenStream) -> TokenStream {
+    #[cfg(all(feature = "log", not(feature = "log-kv")))]
+    {
+        let wrapped = syn::parse_quote_spanned!(Span::call_site() => log::warn);
+        wrap(wrapped, input)
+    }
+    #[cfg(feature = "log-kv")]
+    {
+        like_log::wrap_level("Warn", input)
+    }
+    #[cfg(feature = "tracing")]
+    {
+        like_tracing::wrap("warn", input)
+    }
+}
+
+/// Enhanced version of error! with dot notation and arbitrary expression support
+///
+/// This macro wraps the standard error! macro with support for
+/// dot notation and arbitrary expressions with automatic expression deduplication.
+///
+/// # Example
+///
+/// ```
+/// use formati::error;
+///
+/// struct User {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let user = User {
+///    id: 42,
+///    name: String::from("Alice"),
+/// };
+///
+/// error!("Failed to process user {user.name} with ID {user.id}");
+/// ```
+#[proc_macro]
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub fn error(input: TokenStream) -> TokenStream {
+    #[cfg(all(feature = "log", not(feature = "log-kv")))]
+    {
+        let wrapped = syn::parse_quote_spanned!(Span::call_site() => log::error);
+        wrap(wrapped, input)
+    }
+    #[cfg(feature = "log-kv")]
+    {
+        like_log::wrap_level("Error", input)
+    }
+    #[cfg(feature = "tracing")]
+    {
+        like_tracing::wrap("error", input)
+    }
+}
@@ -0,0 +1,150 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    Expr, ExprAssign, Ident, LitStr, Token,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+use crate::formati_args::{format_args_tokens, str_lit};
+
+/// [`target:` expr `,`]
+fn parse_target(input: ParseStream<'_>) -> syn::Result<Option<Expr>> {
+    if input.peek(Ident) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+        let ident: Ident = input.parse()?;
+        if ident != "target" {
+            return Err(syn::Error::new(ident.span(), "expected `target:`"));
+        }
+        let _: Token![:] = input.parse()?;
+        let target: Expr = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        Ok(Some(target))
+    } else {
+        Ok(None)
+    }
+}
+
+/// [key `=` value `,`]* `"literal"` [`,` expr]*
+struct Message {
+    fields: Vec<ExprAssign>,
+    fmt_lit: LitStr,
+    rest: Vec<Expr>,
+}
+
+impl Parse for Message {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut args = Punctuated::<Expr, Token![,]>::parse_terminated(input)?.into_iter();
+
+        // everything before the message literal is a `key = value` field
+        let mut fields = Vec::new();
+        let fmt_lit = loop {
+            match args.next() {
+                Some(Expr::Assign(field)) => fields.push(field),
+                Some(other) => match str_lit(&other) {
+                    Some(fmt_lit) => break fmt_lit.clone(),
+                    None => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected a `key = value` field or the message literal",
+                        ));
+                    }
+                },
+                None => return Err(input.error("expected a string literal message")),
+            }
+        };
+
+        Ok(Self {
+            fields,
+            fmt_lit,
+            rest: args.collect(),
+        })
+    }
+}
+
+/// input: [`target:` expr `,`] level `,` [key `=` value `,`]* `"literal"` [`,` expr]*
+struct KvInput {
+    target: Option<Expr>,
+    level: Expr,
+    message: Message,
+}
+
+impl Parse for KvInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let target = parse_target(input)?;
+        let level: Expr = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let message: Message = input.parse()?;
+
+        Ok(Self {
+            target,
+            level,
+            message,
+        })
+    }
+}
+
+/// input: [`target:` expr `,`] [key `=` value `,`]* `"literal"` [`,` expr]*
+struct LevelInput {
+    target: Option<Expr>,
+    message: Message,
+}
+
+impl Parse for LevelInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let target = parse_target(input)?;
+        let message: Message = input.parse()?;
+        Ok(Self { target, message })
+    }
+}
+
+/// Wrap `log::log!` with structured `key = value` fields and an interpolated message
+pub fn wrap_kv(input: TokenStream) -> TokenStream {
+    let KvInput {
+        target,
+        level,
+        message,
+    } = parse_macro_input!(input as KvInput);
+
+    expand(target, quote!(#level), message)
+}
+
+/// Wrap `log::log!` at a fixed level (`trace!`, `info!`, ...), accepting the
+/// same target, fields and message as [`wrap_kv`]
+pub fn wrap_level(level: &str, input: TokenStream) -> TokenStream {
+    let LevelInput { target, message } = parse_macro_input!(input as LevelInput);
+
+    let level = Ident::new(level, Span::call_site());
+    expand(target, quote!(::log::Level::#level), message)
+}
+
+fn expand(target: Option<Expr>, level: TokenStream2, message: Message) -> TokenStream {
+    let Message {
+        fields,
+        fmt_lit,
+        rest,
+    } = message;
+
+    let target = target.map(|target| quote!(target: #target,));
+    let args = match format_args_tokens(&fmt_lit, rest) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // `log` only accepts the `;` separator when there is at least one field
+    let fields: TokenStream2 = if fields.is_empty() {
+        quote!()
+    } else {
+        let (keys, values): (Vec<_>, Vec<_>) = fields
+            .into_iter()
+            .map(|field| (field.left, field.right))
+            .unzip();
+        quote!(#(#keys = #values),*;)
+    };
+
+    quote! {
+        ::log::log!(#target #level, #fields #args)
+    }
+    .into()
+}
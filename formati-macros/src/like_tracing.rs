@@ -0,0 +1,112 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Spacing, Span, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{parse::Parser, parse2, punctuated::Punctuated, Expr, Ident, Token};
+
+use crate::formati_args::{format_args_tokens, str_lit};
+
+/// Split on *top-level* commas — nothing else
+fn split_top_level(stream: TokenStream2) -> Vec<TokenStream2> {
+    let mut segs = Vec::<TokenStream2>::new();
+    let mut cur = TokenStream2::new();
+    for tt in stream {
+        match &tt {
+            TokenTree::Punct(p) if p.as_char() == ',' && p.spacing() == Spacing::Alone => {
+                segs.push(cur);
+                cur = TokenStream2::new();
+            }
+            _ => cur.extend(std::iter::once(tt)),
+        }
+    }
+    segs.push(cur);
+    segs
+}
+
+/// Find format string and process tracing-like macros
+///
+/// `tracing` takes its directives (`target:`, `parent:`, ...) and fields before the
+/// message, and none of those can be a bare string literal, so the *first*
+/// literal segment is the template; later literals are ordinary format arguments.
+/// Events without a message are forwarded untouched.
+pub fn wrap(kind: &str, input: proc_macro::TokenStream) -> TokenStream {
+    let input = TokenStream2::from(input);
+    let tracing_macro = syn::Ident::new(kind, proc_macro2::Span::call_site());
+    let segments = split_top_level(input.clone());
+
+    let template = segments.iter().enumerate().find_map(|(idx, seg)| {
+        let expr = parse2::<Expr>(seg.clone()).ok()?;
+        str_lit(&expr).map(|lit| (idx, lit.clone()))
+    });
+    let Some((split_at, lit_str)) = template else {
+        return quote!(::tracing::#tracing_macro!(#input)).into();
+    };
+
+    let (front, back) = segments.split_at(split_at);
+    let rest = &back[1..]; // possible extra exprs
+
+    // extra args (after the literal), skipping empty segments from trailing commas
+    let rest: syn::Result<Vec<Expr>> = rest
+        .iter()
+        .filter(|seg| !seg.is_empty())
+        .map(|seg| parse2(seg.clone()))
+        .collect();
+    let args = match rest.and_then(|rest| format_args_tokens(&lit_str, rest)) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // emit the real tracing macro call
+    let front: Vec<&TokenStream2> = front.iter().collect();
+
+    quote! {
+        ::tracing::#tracing_macro!(
+            #(#front ,)*
+            #args
+        )
+    }
+    .into()
+}
+
+/// `trace_ret!(expr, "template", args...)`: evaluate `expr` once, emit a
+/// `trace!` event with the template as its message and `expr`'s `Debug` as the
+/// `return` field (like `#[instrument(ret)]`), then hand the value back
+pub fn wrap_ret(input: proc_macro::TokenStream) -> TokenStream {
+    let args = match Punctuated::<Expr, Token![,]>::parse_terminated.parse(input) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let mut args = args.into_iter();
+    let Some(value) = args.next() else {
+        return syn::Error::new(Span::call_site(), "trace_ret!: expected a value")
+            .to_compile_error()
+            .into();
+    };
+    let rest: Vec<Expr> = args.collect();
+
+    // mixed-site, so the message's own expressions can't see the binding
+    let tmp = Ident::new("tmp", Span::mixed_site());
+
+    let event = match rest.first().and_then(str_lit).cloned() {
+        Some(fmt_lit) => match format_args_tokens(&fmt_lit, rest.into_iter().skip(1)) {
+            Ok(message) => quote!(::tracing::trace!(return = ?#tmp, #message)),
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None if rest.is_empty() => quote!(::tracing::trace!(return = ?#tmp)),
+        None => {
+            return syn::Error::new_spanned(&rest[0], "trace_ret!: expected a format string")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    quote! {
+        match #value {
+            #tmp => {
+                #event;
+                #tmp
+            }
+        }
+    }
+    .into()
+}
@@ -0,0 +1,48 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{LitStr, parse_macro_input};
+
+use crate::formati_args::{Input, expand, placeholder_end, split_head_spec};
+
+/// Like [`wrap`](crate::formati_args::wrap), but only plain `{expr}` placeholders are accepted: any format
+/// spec is a compile error
+pub fn wrap(wrapped: TokenStream2, input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    match reject_specs(&fmt_lit).and_then(|()| expand(wrapped, &[], &fmt_lit, rest)) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Error on the first placeholder that carries a `:spec`
+fn reject_specs(fmt_lit: &LitStr) -> syn::Result<()> {
+    let src = fmt_lit.value();
+    let mut rest = src.as_str();
+
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open..];
+        if rest.starts_with("{{") {
+            rest = &rest[2..];
+            continue;
+        }
+
+        // an unclosed placeholder is reported by `formati_args`
+        let Some(end) = placeholder_end(rest, 1) else {
+            return Ok(());
+        };
+        let (head, spec) = split_head_spec(&rest[1..end - 1]);
+        if !spec.is_empty() {
+            return Err(syn::Error::new(
+                fmt_lit.span(),
+                format!(
+                    "uformat_min!: format specs are not supported, found `{{{}:{}}}`; use a plain `{{{}}}`",
+                    head, spec, head
+                ),
+            ));
+        }
+        rest = &rest[end..];
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Expr, ExprUnary, LitStr, UnOp, parse_macro_input};
+
+use crate::formati_args::{CallerArgs, Extracted, Extraction, Input, arg_name, formati_args};
+
+/// A place such as `user.name` or `rows[0]`, which is bound by reference
+/// rather than moved out of. A field of a temporary (`lock().unwrap().0`) is
+/// not one, so the temporary is still dropped straight away
+fn is_place(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(inner) => is_place(&inner.expr),
+        Expr::Group(inner) => is_place(&inner.expr),
+        Expr::Path(_) => true,
+        Expr::Field(field) => is_place(&field.base),
+        Expr::Index(index) => is_place(&index.expr),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Deref(_),
+            expr,
+            ..
+        }) => is_place(expr),
+        _ => false,
+    }
+}
+
+/// Format with `std::format!`, first binding each extracted expression in its
+/// own `let`, so temporaries it creates (e.g. a lock guard or `RefCell` borrow)
+/// are dropped before the next one is evaluated. A value that is a reference
+/// is bound as its `ToOwned` form, so it outlives the temporary it borrowed
+/// from; places are borrowed, and anything else is bound as is
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    let CallerArgs {
+        positional,
+        named,
+        reusable,
+    } = match CallerArgs::split(rest) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let Extraction { lit, extracted, .. } = match formati_args(&fmt_lit, &reusable) {
+        Ok(extraction) => extraction,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let lit = LitStr::new(&lit, fmt_lit.span());
+
+    let mut bindings = Vec::new();
+    let mut args = Vec::new();
+    for (idx, Extracted { expr, checks }) in extracted.into_iter().enumerate() {
+        let name = arg_name(idx);
+        bindings.push(match is_place(&expr) {
+            true => quote!(let #name = &#expr;),
+            false => quote!(let #name = (&::formati::__private::Bind::new(#expr)).bind();),
+        });
+
+        let value = Extracted {
+            expr: syn::parse_quote!(#name),
+            checks,
+        }
+        .into_value(fmt_lit.span());
+        args.push(quote!(#name = #value));
+    }
+
+    quote! {
+        {
+            use ::formati::__private::{ViaToOwned as _, ViaValue as _};
+            #(#bindings)*
+            ::formati::__private::alloc::format!(
+                #lit
+                #(, #positional)*
+                #(, #named)*
+                #(, #args)*
+            )
+        }
+    }
+    .into()
+}
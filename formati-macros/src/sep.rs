@@ -0,0 +1,44 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Expr, LitStr, Token, parse_macro_input, punctuated::Punctuated};
+
+use crate::formati_args::str_lit;
+
+/// `format_sep!(" | ", a, b.c, ...)`: format each argument with `Display`,
+/// joined by the literal separator, by building the `{} | {} | ...` template
+/// for `std::format!`
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let mut args = args.into_iter();
+    let sep = match args.next() {
+        Some(expr) => match str_lit(&expr) {
+            Some(sep) => sep.clone(),
+            None => {
+                return syn::Error::new_spanned(
+                    expr,
+                    "format_sep!: expected a string literal separator",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        None => {
+            return syn::Error::new(Span::call_site(), "format_sep!: expected a separator")
+                .to_compile_error()
+                .into();
+        }
+    };
+    let values: Vec<Expr> = args.collect();
+
+    if values.is_empty() {
+        return quote!(::formati::__private::alloc::String::new()).into();
+    }
+
+    let sep_text = sep.value().replace('{', "{{").replace('}', "}}");
+    let template = vec!["{}"; values.len()].join(&sep_text);
+    let lit = LitStr::new(&template, sep.span());
+
+    quote!(::formati::__private::alloc::format!(#lit #(, #values)*)).into()
+}
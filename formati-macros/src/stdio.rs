@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Expr, Ident, Token, parse_macro_input, punctuated::Punctuated};
+
+use crate::formati_args::{Input, expand, format_args_tokens, formati_args, str_lit};
+
+/// Format once with `std::format!`, hand the result to `sink` (e.g. `std::println`)
+/// and return the `String`, so the extracted expressions are only evaluated once
+pub fn wrap_returning(sink: TokenStream2, input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    let formatted = match expand(
+        quote!(formati::__private::alloc::format),
+        &[],
+        &fmt_lit,
+        rest,
+    ) {
+        Ok(formatted) => formatted,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    TokenStream::from(quote! {
+        {
+            let __formati_s: ::formati::__private::alloc::String = #formatted;
+            ::#sink!("{}", __formati_s);
+            __formati_s
+        }
+    })
+}
+
+/// `expr, "label", args...`: evaluate `expr` once, print the label and the
+/// value's `Debug` to stderr, and return the value
+pub fn wrap_tee(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let mut args = args.into_iter();
+    let Some(value) = args.next() else {
+        return syn::Error::new(Span::call_site(), "tee!: expected a value")
+            .to_compile_error()
+            .into();
+    };
+    let rest: Vec<Expr> = args.collect();
+
+    // mixed-site, so the label's own expressions can't see the binding
+    let tmp = Ident::new("tmp", Span::mixed_site());
+
+    let print = match rest.first().and_then(str_lit).cloned() {
+        Some(fmt_lit) => match format_args_tokens(&fmt_lit, rest.into_iter().skip(1)) {
+            Ok(label) => quote! {
+                ::std::eprintln!("{}: {:?}", ::core::format_args!(#label), &#tmp)
+            },
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None if rest.is_empty() => quote!(::std::eprintln!("{:?}", &#tmp)),
+        None => {
+            return syn::Error::new_spanned(&rest[0], "tee!: expected a format string")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    quote! {
+        match #value {
+            #tmp => {
+                #print;
+                #tmp
+            }
+        }
+    }
+    .into()
+}
+
+/// `dbg!` takes values rather than a template, so a leading literal is passed
+/// to `std::dbg!` as is, followed by each distinct expression extracted from
+/// its placeholders and then the remaining arguments
+pub fn wrap_dbg(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let mut args = args.into_iter();
+    let first = args.next();
+    let extracted = match first.as_ref().and_then(str_lit) {
+        Some(fmt_lit) => match formati_args(fmt_lit, &HashMap::new()) {
+            Ok(extraction) => extraction.extracted,
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => Vec::new(),
+    };
+
+    let args = first
+        .into_iter()
+        .chain(extracted.into_iter().map(|extracted| extracted.expr))
+        .chain(args);
+    quote!(::std::dbg!(#(#args),*)).into()
+}
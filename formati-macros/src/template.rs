@@ -0,0 +1,126 @@
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{
+    Attribute, Expr, Ident, LitStr, Token, Visibility,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+use crate::formati_args::format_args_tokens;
+
+/// input: [attrs] [vis] NAME `=` "literal" [`;`]
+struct Definition {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    lit: LitStr,
+}
+
+impl Parse for Definition {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let name = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let lit = input.parse()?;
+        let _: Option<Token![;]> = input.parse()?;
+        Ok(Self {
+            attrs,
+            vis,
+            name,
+            lit,
+        })
+    }
+}
+
+/// A `const NAME: &str` holding the template, and a `macro_rules! NAME` that
+/// hands the literal itself back to `format_const!`
+pub fn define(input: TokenStream) -> TokenStream {
+    let Definition {
+        attrs,
+        vis,
+        name,
+        lit,
+    } = parse_macro_input!(input as Definition);
+
+    quote! {
+        #(#attrs)*
+        #vis const #name: &str = #lit;
+
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            (@formati $caller:ident $($args:tt)*) => {
+                ::formati::__format_template!($caller, #lit $($args)*)
+            };
+        }
+    }
+    .into()
+}
+
+/// `NAME, args...` as a call to the template's own macro, passing `NAME`
+/// along so the template can be resolved where `format_const!` was written
+pub fn format_const(input: TokenStream) -> TokenStream {
+    let input = TokenStream2::from(input);
+    let mut tokens = input.clone().into_iter();
+
+    let (name, rest) = match (tokens.next(), tokens.clone().next()) {
+        (Some(TokenTree::Ident(name)), None) => (name, TokenStream2::new()),
+        (Some(TokenTree::Ident(name)), Some(TokenTree::Punct(punct))) if punct.as_char() == ',' => {
+            (name, tokens.collect())
+        }
+        (Some(TokenTree::Literal(_)), _) => {
+            return syn::Error::new_spanned(
+                input,
+                "format_const!: expected the name of a `formati::template!`; \
+                 a literal template can be passed to `formati::format!` directly",
+            )
+            .to_compile_error()
+            .into();
+        }
+        _ => {
+            return syn::Error::new_spanned(
+                input,
+                "format_const!: expected the name of a template defined with \
+                 `formati::template!`; only those are known while macros expand",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    quote!(#name!(@formati #name #rest)).into()
+}
+
+/// input: caller `,` "literal" [`,` expr ]*
+struct Expansion {
+    caller: Ident,
+    lit: LitStr,
+    rest: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for Expansion {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let caller = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let lit = input.parse()?;
+        let rest = match input.parse::<Option<Token![,]>>()? {
+            Some(_) => Punctuated::parse_terminated(input)?,
+            None => Punctuated::new(),
+        };
+        Ok(Self { caller, lit, rest })
+    }
+}
+
+/// Format the template as if its literal had been written at `caller`, so its
+/// placeholders see the caller's variables rather than the definition's
+pub fn expand(input: TokenStream) -> TokenStream {
+    let Expansion { caller, lit, rest } = parse_macro_input!(input as Expansion);
+
+    let lit = LitStr::new(&lit.value(), lit.span().resolved_at(caller.span()));
+    match format_args_tokens(&lit, rest) {
+        Ok(tokens) => quote!(::formati::__private::alloc::format!(#tokens)).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
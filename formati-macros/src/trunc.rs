@@ -0,0 +1,40 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Expr, Token, parse_macro_input, punctuated::Punctuated};
+
+use crate::formati_args::{format_args_tokens, str_lit};
+
+/// `max, ellipsis, "template", args...`: format with `std::format!`, then cut
+/// the `String` to at most `max` chars, ellipsis included
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let mut args = args.into_iter();
+    let (Some(max), Some(ellipsis)) = (args.next(), args.next()) else {
+        return syn::Error::new(
+            Span::call_site(),
+            "format_trunc!: expected a maximum length and an ellipsis",
+        )
+        .to_compile_error()
+        .into();
+    };
+    let rest: Vec<Expr> = args.collect();
+
+    let Some(fmt_lit) = rest.first().and_then(str_lit).cloned() else {
+        return syn::Error::new(
+            Span::call_site(),
+            "format_trunc!: expected a format string after the maximum length and ellipsis",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    match format_args_tokens(&fmt_lit, rest.into_iter().skip(1)) {
+        Ok(tokens) => quote! {
+            ::formati::__private::truncate(::formati::__private::alloc::format!(#tokens), #max, #ellipsis)
+        }
+        .into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
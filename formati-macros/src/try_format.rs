@@ -0,0 +1,48 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Ident, parse_macro_input};
+
+use crate::bound::BoundTemplate;
+use crate::formati_args::{Input, arg_name};
+
+/// Format into a `Result<String, FormatError>`: each value goes through
+/// `TryArg`, which picks `TryDisplay` over `Display` by autoref method
+/// resolution, and the first `TryDisplay` failure is returned instead of the
+/// `String`
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let Input { fmt_lit, rest } = parse_macro_input!(input as Input);
+
+    let bound = match BoundTemplate::parse("try_format!", &fmt_lit, rest) {
+        Ok(bound) => bound,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let lit = bound.template(&fmt_lit, |(value, spec)| match spec.is_empty() {
+        true => format!("{{{}}}", arg_name(*value)),
+        false => format!("{{{}:{}}}", arg_name(*value), spec),
+    });
+
+    let failure = Ident::new("failure", Span::mixed_site());
+    let out = Ident::new("out", Span::mixed_site());
+    let bindings = bound.bindings();
+    let args = bindings.iter().enumerate().map(|(idx, binding)| {
+        let name = arg_name(idx);
+        quote!(#name = (&::formati::__private::TryArg::new(#binding, &#failure)).try_arg())
+    });
+
+    bound
+        .bind(quote! {
+            {
+                use ::formati::__private::{ViaDisplay as _, ViaTryDisplay as _};
+                let #failure = ::formati::__private::Failure::default();
+                let mut #out = ::formati::__private::alloc::String::new();
+                let result = ::core::fmt::Write::write_fmt(
+                    &mut #out,
+                    ::core::format_args!(#lit #(, #args)*),
+                );
+                #failure.finish(result, #out)
+            }
+        })
+        .into()
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    Expr, LitStr, Token,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+};
+
+use crate::formati_args::{placeholder_end, split_head_spec};
+
+/// input: expr `,` `"literal"` [`,`]
+struct Input {
+    table: Expr,
+    fmt_lit: LitStr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let table: Expr = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let fmt_lit: LitStr = input.parse()?;
+
+        // allow a single trailing comma, nothing else
+        if input.peek(Token![,]) {
+            let _: Token![,] = input.parse()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("vformat! takes only a table and a template literal"));
+        }
+
+        Ok(Self { table, fmt_lit })
+    }
+}
+
+/// Rewrite `{name[:spec]}` placeholders to indices into the names resolved at runtime
+fn table_args(fmt_lit: &LitStr) -> syn::Result<(String, Vec<String>)> {
+    let src = fmt_lit.value();
+    let mut out_lit = String::with_capacity(src.len());
+    let mut names = Vec::<String>::new();
+    let mut name_map: HashMap<String, usize> = HashMap::new();
+
+    let mut rest = src.as_str();
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            out_lit.push_str(&rest[..2]);
+            rest = &rest[2..];
+            continue;
+        }
+        if c != '{' {
+            out_lit.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let Some(end) = placeholder_end(rest, 1) else {
+            return Err(syn::Error::new(
+                fmt_lit.span(),
+                "vformat!: unmatched `{` in template",
+            ));
+        };
+        let (head, spec) = split_head_spec(&rest[1..end - 1]);
+        rest = &rest[end..];
+
+        let name = head.trim();
+        if name.is_empty() {
+            return Err(syn::Error::new(
+                fmt_lit.span(),
+                "vformat! placeholders must name a table entry, e.g. `{name}`",
+            ));
+        }
+
+        let idx = *name_map.entry(name.to_string()).or_insert_with(|| {
+            names.push(name.to_string());
+            names.len() - 1
+        });
+
+        out_lit.push('{');
+        out_lit.push_str(&idx.to_string());
+        if !spec.is_empty() {
+            out_lit.push(':');
+            out_lit.push_str(spec);
+        }
+        out_lit.push('}');
+    }
+
+    Ok((out_lit, names))
+}
+
+/// Format a template against a table of named values resolved at runtime
+pub fn wrap(input: TokenStream) -> TokenStream {
+    let Input { table, fmt_lit } = parse_macro_input!(input as Input);
+
+    let (out_lit, names) = match table_args(&fmt_lit) {
+        Ok(parsed) => parsed,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let lit = LitStr::new(&out_lit, fmt_lit.span());
+    let bindings: Vec<_> = (0..names.len())
+        .map(|idx| quote::format_ident!("__formati_{}", idx))
+        .collect();
+
+    quote! {
+        ::formati::__private::vformat_resolve(&(#table), [#(#names),*])
+            .map(|[#(#bindings),*]| ::formati::__private::alloc::format!(#lit #(, #bindings)*))
+    }
+    .into()
+}
@@ -0,0 +1,107 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Expr, Token, parse_macro_input, punctuated::Punctuated};
+
+use crate::formati_args::{format_args_tokens, str_lit, wrap_after};
+
+/// Like [`wrap_after`](crate::formati_args::wrap_after) for `write!`-like macros, with `fmt::Write` in scope so
+/// a `String` destination needs no `use`. `io::Write` is left out: a type
+/// implementing both would make `write_fmt` ambiguous, see [`wrap_io_write`]
+pub fn wrap_write(wrapped: TokenStream2, input: TokenStream) -> TokenStream {
+    let tokens = TokenStream2::from(wrap_after(wrapped, 1, input));
+    quote! {
+        {
+            use ::core::fmt::Write as _;
+            #tokens
+        }
+    }
+    .into()
+}
+
+/// Write a line into a `core::fmt::Write` sink through
+/// `formati::__private::FmtWriteLine`, so a type that also implements
+/// `io::Write` always resolves to the `fmt` side
+pub fn wrap_fmt_writeln(input: TokenStream) -> TokenStream {
+    wrap_sink_method(
+        "fmt_writeln",
+        quote!(FmtWriteLine),
+        quote!(formati_write_line),
+        input,
+    )
+}
+
+/// Write into a `std::io::Write` sink through `formati::__private::IoWriteFmt`,
+/// so a type that also implements `fmt::Write` always resolves to the `io` side
+pub fn wrap_io_write(input: TokenStream) -> TokenStream {
+    wrap_sink_method(
+        "io_write",
+        quote!(IoWriteFmt),
+        quote!(formati_io_write),
+        input,
+    )
+}
+
+/// Like [`wrap_io_write`], ending the output with a newline
+pub fn wrap_io_writeln(input: TokenStream) -> TokenStream {
+    wrap_sink_method(
+        "io_writeln",
+        quote!(IoWriteFmt),
+        quote!(formati_io_write_line),
+        input,
+    )
+}
+
+/// Append to a `String` through `formati::__private::WriteLen`, evaluating to
+/// the number of bytes appended
+pub fn wrap_write_len(input: TokenStream) -> TokenStream {
+    wrap_sink_method(
+        "write_len",
+        quote!(WriteLen),
+        quote!(formati_write_len),
+        input,
+    )
+}
+
+/// `sink, "template", args...` as `sink.method(format_args!(...))`, with the
+/// method's trait from `formati::__private` in scope
+fn wrap_sink_method(
+    name: &str,
+    helper: TokenStream2,
+    method: TokenStream2,
+    input: TokenStream,
+) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+
+    let mut args = args.into_iter();
+    let Some(sink) = args.next() else {
+        return syn::Error::new(Span::call_site(), format!("{}!: expected a sink", name))
+            .to_compile_error()
+            .into();
+    };
+    let rest: Vec<Expr> = args.collect();
+
+    let tokens = match rest.first().and_then(str_lit) {
+        Some(fmt_lit) => match format_args_tokens(&fmt_lit.clone(), rest.into_iter().skip(1)) {
+            Ok(tokens) => tokens,
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None if rest.is_empty() => quote!(""),
+        None => {
+            return syn::Error::new_spanned(
+                &rest[0],
+                format!("{}!: expected a format string", name),
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    quote! {
+        {
+            use ::formati::__private::#helper as _;
+            (#sink).#method(::core::format_args!(#tokens))
+        }
+    }
+    .into()
+}
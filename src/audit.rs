@@ -0,0 +1,30 @@
+/// How a [`format_audit!`](crate::format_audit) template was deduplicated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormatAudit {
+    placeholders: usize,
+    unique_expressions: usize,
+}
+
+impl FormatAudit {
+    /// Placeholders holding an extracted expression, counting repeats
+    pub const fn placeholders(&self) -> usize {
+        self.placeholders
+    }
+
+    /// Distinct extracted expressions, i.e. how many are actually evaluated
+    pub const fn unique_expressions(&self) -> usize {
+        self.unique_expressions
+    }
+
+    /// Evaluations saved by deduplication
+    pub const fn saved_evaluations(&self) -> usize {
+        self.placeholders - self.unique_expressions
+    }
+}
+
+pub const fn new(placeholders: usize, unique_expressions: usize) -> FormatAudit {
+    FormatAudit {
+        placeholders,
+        unique_expressions,
+    }
+}
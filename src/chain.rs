@@ -0,0 +1,28 @@
+//! The value a `{err:chain}` placeholder formats
+
+use core::error::Error;
+use core::fmt;
+
+/// Errors whose `source()` chain can be rendered after their own message
+pub trait FormatChain: Error {
+    fn formati_chain(&self) -> Chain<'_, Self> {
+        Chain(self)
+    }
+}
+
+impl<E: Error + ?Sized> FormatChain for E {}
+
+/// Writes an error followed by each of its sources, separated by `: `
+pub struct Chain<'a, E: ?Sized>(&'a E);
+
+impl<E: Error + ?Sized> fmt::Display for Chain<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        let mut source = self.0.source();
+        while let Some(err) = source {
+            write!(f, ": {}", err)?;
+            source = err.source();
+        }
+        Ok(())
+    }
+}
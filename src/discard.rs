@@ -0,0 +1,12 @@
+//! The value a `{_ = expr}` placeholder formats when nothing else shows `expr`
+
+use core::fmt;
+
+/// Holds the evaluated expression and writes nothing
+pub struct Discard<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ?Sized> fmt::Display for Discard<'_, T> {
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+//! The value a `{elapsed:ms}` / `{elapsed:ms.2}` placeholder formats
+
+use core::fmt;
+use core::time::Duration;
+
+/// Writes a `Duration` as a number of some unit, e.g. `1.5` for 1500µs in ms
+pub struct DurationIn {
+    value: f64,
+    precision: Option<usize>,
+}
+
+impl DurationIn {
+    pub fn new(duration: &Duration, nanos_per_unit: u32, precision: Option<usize>) -> Self {
+        // dividing the exact nanosecond count keeps e.g. 1.234567ms from
+        // picking up float noise
+        let value = duration.as_nanos() as f64 / f64::from(nanos_per_unit);
+        Self { value, precision }
+    }
+}
+
+impl fmt::Display for DurationIn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.precision {
+            Some(precision) => write!(f, "{:.*}", precision, self.value),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
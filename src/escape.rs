@@ -0,0 +1,75 @@
+//! The values `format_html!` / `format_shell!` splice into their template
+
+use alloc::string::String;
+use core::fmt::{self, Write as _};
+
+/// Writes the rendering of `value` (already carrying its placeholder's spec)
+/// through `escape`
+pub struct Escaped<T> {
+    value: T,
+    escape: fn(&str, &mut fmt::Formatter<'_>) -> fmt::Result,
+}
+
+impl<T: fmt::Display> Escaped<T> {
+    /// Escape `<`, `>`, `&`, `"` and `'` as HTML character references
+    pub fn html(value: T) -> Self {
+        Self {
+            value,
+            escape: html,
+        }
+    }
+
+    /// Quote the value as a single POSIX shell word
+    pub fn shell(value: T) -> Self {
+        Self {
+            value,
+            escape: shell,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Escaped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rendered = String::new();
+        write!(rendered, "{}", self.value)?;
+        (self.escape)(&rendered, f)
+    }
+}
+
+fn html(s: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut plain = 0;
+    for (idx, c) in s.char_indices() {
+        let entity = match c {
+            '<' => "&lt;",
+            '>' => "&gt;",
+            '&' => "&amp;",
+            '"' => "&quot;",
+            '\'' => "&#39;",
+            _ => continue,
+        };
+        f.write_str(&s[plain..idx])?;
+        f.write_str(entity)?;
+        plain = idx + 1;
+    }
+    f.write_str(&s[plain..])
+}
+
+/// Words made only of these are left bare, like Python's `shlex.quote`;
+/// anything else is single-quoted, with each `'` written as `'\''`
+fn shell(s: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let bare = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "@%+=:,./_-".contains(c));
+    if bare {
+        return f.write_str(s);
+    }
+
+    f.write_char('\'')?;
+    for (idx, part) in s.split('\'').enumerate() {
+        if idx > 0 {
+            f.write_str("'\\''")?;
+        }
+        f.write_str(part)?;
+    }
+    f.write_char('\'')
+}
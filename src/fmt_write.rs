@@ -0,0 +1,57 @@
+use core::fmt;
+
+/// Line writer behind [`fmt_writeln!`](crate::fmt_writeln), implemented for
+/// every `fmt::Write` so the macro never picks up an `io::Write` method
+pub trait FmtWriteLine: fmt::Write {
+    fn formati_write_line(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        self.write_fmt(args)?;
+        self.write_char('\n')
+    }
+}
+
+impl<W: fmt::Write + ?Sized> FmtWriteLine for W {}
+
+/// Writer behind [`io_write!`](crate::io_write) and
+/// [`io_writeln!`](crate::io_writeln), implemented for every `io::Write` so the
+/// macros never pick up a `fmt::Write` method
+#[cfg(feature = "std")]
+pub trait IoWriteFmt: std::io::Write {
+    fn formati_io_write(&mut self, args: fmt::Arguments<'_>) -> std::io::Result<()> {
+        self.write_fmt(args)
+    }
+
+    fn formati_io_write_line(&mut self, args: fmt::Arguments<'_>) -> std::io::Result<()> {
+        self.write_fmt(args)?;
+        self.write_all(b"\n")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> IoWriteFmt for W {}
+
+/// Render a message to a `String`, for custom sinks that receive the
+/// `fmt::Arguments` of [`format_args!`](crate::format_args): a `Log` or
+/// `Subscriber`-like type takes the arguments and keeps the rendered line.
+/// A message without arguments is copied without going through the formatter.
+#[cfg(feature = "alloc")]
+pub fn fmt_line(args: fmt::Arguments<'_>) -> alloc::string::String {
+    alloc::fmt::format(args)
+}
+
+/// Appender behind [`write_len!`](crate::write_len), reporting how many bytes
+/// the formatted text took
+#[cfg(feature = "alloc")]
+pub trait WriteLen {
+    fn formati_write_len(&mut self, args: fmt::Arguments<'_>) -> usize;
+}
+
+#[cfg(feature = "alloc")]
+impl WriteLen for alloc::string::String {
+    fn formati_write_len(&mut self, args: fmt::Arguments<'_>) -> usize {
+        let before = self.len();
+        // like `format!`, a `Display` impl failing is its own bug; whatever it
+        // managed to write is still counted
+        let _ = fmt::Write::write_fmt(self, args);
+        self.len() - before
+    }
+}
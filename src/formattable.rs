@@ -0,0 +1,9 @@
+//! The value a `{value:*}` placeholder formats
+
+use alloc::string::String;
+
+/// A canonical string form, written in place of a `{value:*}` placeholder. For
+/// types that render one way only and don't implement `Display`
+pub trait Formattable {
+    fn formati(&self) -> String;
+}
@@ -0,0 +1,40 @@
+//! The value a `{items:join("sep")}` placeholder formats
+
+use core::fmt;
+
+/// Writes each item of `items` with `write_item`, separated by `sep`
+pub struct Join<T, F> {
+    items: T,
+    sep: &'static str,
+    write_item: F,
+}
+
+impl<T, F> Join<T, F>
+where
+    T: Copy + IntoIterator,
+    F: Fn(T::Item, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    pub fn new(items: T, sep: &'static str, write_item: F) -> Self {
+        Self {
+            items,
+            sep,
+            write_item,
+        }
+    }
+}
+
+impl<T, F> fmt::Display for Join<T, F>
+where
+    T: Copy + IntoIterator,
+    F: Fn(T::Item, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, item) in self.items.into_iter().enumerate() {
+            if idx > 0 {
+                f.write_str(self.sep)?;
+            }
+            (self.write_item)(item, f)?;
+        }
+        Ok(())
+    }
+}
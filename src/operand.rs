@@ -0,0 +1,36 @@
+//! Operand display for `assert!(a <op> b)` failures: an operand's `Debug` when
+//! its type has one, and `_` otherwise, picked by autoref method resolution
+
+use core::fmt;
+
+pub struct Operand<'a, T>(pub &'a T);
+
+/// Preferred: taken when `T: Debug`, as it matches `&Operand` without autoref
+pub trait ViaDebug {
+    fn operand(&self) -> &dyn fmt::Debug;
+}
+
+impl<T: fmt::Debug> ViaDebug for Operand<'_, T> {
+    fn operand(&self) -> &dyn fmt::Debug {
+        self.0
+    }
+}
+
+/// Fallback for operands without `Debug`
+pub trait ViaOpaque {
+    fn operand(&self) -> &dyn fmt::Debug;
+}
+
+impl<T> ViaOpaque for &Operand<'_, T> {
+    fn operand(&self) -> &dyn fmt::Debug {
+        &Opaque
+    }
+}
+
+struct Opaque;
+
+impl fmt::Debug for Opaque {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("_")
+    }
+}
@@ -0,0 +1,44 @@
+//! Bindings for `format_owned!`: a borrow is turned into its owned form, so it
+//! outlives the temporaries it borrowed from, and any other value is kept as
+//! is, picked by autoref method resolution
+
+use alloc::borrow::ToOwned;
+use core::cell::Cell;
+
+pub struct Bind<T>(Cell<Option<T>>);
+
+impl<T> Bind<T> {
+    pub fn new(value: T) -> Self {
+        Self(Cell::new(Some(value)))
+    }
+
+    fn take(&self) -> T {
+        self.0.take().expect("a `Bind` is only bound once")
+    }
+}
+
+/// Preferred: taken for a `&U`, as it matches `&Bind` without autoref
+pub trait ViaToOwned {
+    type Owned;
+
+    fn bind(&self) -> Self::Owned;
+}
+
+impl<U: ToOwned + ?Sized> ViaToOwned for Bind<&U> {
+    type Owned = U::Owned;
+
+    fn bind(&self) -> U::Owned {
+        self.take().to_owned()
+    }
+}
+
+/// Fallback: the value itself
+pub trait ViaValue<T> {
+    fn bind(&self) -> T;
+}
+
+impl<T> ViaValue<T> for &Bind<T> {
+    fn bind(&self) -> T {
+        self.take()
+    }
+}
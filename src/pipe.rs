@@ -0,0 +1,8 @@
+//! The value a `{value | |v| render(v)}` placeholder formats
+
+/// Calls `render` on a borrow of the placeholder's value. A function rather
+/// than an immediately called closure, so the closure's parameter type is
+/// inferred from `value`
+pub fn pipe<T: ?Sized, R>(value: &T, render: impl FnOnce(&T) -> R) -> R {
+    render(value)
+}
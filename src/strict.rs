@@ -0,0 +1,36 @@
+//! Identity functions the `strict` feature passes extracted expressions through,
+//! so a missing formatting trait is reported against the expression itself
+
+use core::fmt;
+
+pub fn display<T: ?Sized + fmt::Display>(value: &T) -> &T {
+    value
+}
+
+pub fn debug<T: ?Sized + fmt::Debug>(value: &T) -> &T {
+    value
+}
+
+pub fn lower_hex<T: ?Sized + fmt::LowerHex>(value: &T) -> &T {
+    value
+}
+
+pub fn upper_hex<T: ?Sized + fmt::UpperHex>(value: &T) -> &T {
+    value
+}
+
+pub fn octal<T: ?Sized + fmt::Octal>(value: &T) -> &T {
+    value
+}
+
+pub fn binary<T: ?Sized + fmt::Binary>(value: &T) -> &T {
+    value
+}
+
+pub fn lower_exp<T: ?Sized + fmt::LowerExp>(value: &T) -> &T {
+    value
+}
+
+pub fn upper_exp<T: ?Sized + fmt::UpperExp>(value: &T) -> &T {
+    value
+}
@@ -0,0 +1,23 @@
+//! The value a `{x:red}` / `{x:red.bold}` placeholder formats
+
+use core::fmt;
+
+/// Writes `value` between `escape` and the ANSI reset
+pub struct Styled<'a, T: ?Sized> {
+    value: &'a T,
+    escape: &'static str,
+}
+
+impl<'a, T: ?Sized> Styled<'a, T> {
+    pub fn new(value: &'a T, escape: &'static str) -> Self {
+        Self { value, escape }
+    }
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for Styled<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.escape)?;
+        self.value.fmt(f)?;
+        f.write_str("\x1b[0m")
+    }
+}
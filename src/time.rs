@@ -0,0 +1,46 @@
+//! The value a `{when:time(%H:%M:%S)}` placeholder formats
+
+use chrono::format::{DelayedFormat, StrftimeItems};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use core::fmt;
+use std::time::SystemTime;
+
+/// Timestamps that can be rendered with a strftime-like format string
+pub trait FormatTime {
+    fn formati_time<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>>;
+}
+
+/// A `SystemTime` is rendered in UTC
+impl FormatTime for SystemTime {
+    fn formati_time<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        DateTime::<Utc>::from(*self).format(fmt)
+    }
+}
+
+impl<Tz> FormatTime for DateTime<Tz>
+where
+    Tz: TimeZone,
+    Tz::Offset: fmt::Display,
+{
+    fn formati_time<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        self.format(fmt)
+    }
+}
+
+impl FormatTime for NaiveDateTime {
+    fn formati_time<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        self.format(fmt)
+    }
+}
+
+impl FormatTime for NaiveDate {
+    fn formati_time<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        self.format(fmt)
+    }
+}
+
+impl FormatTime for NaiveTime {
+    fn formati_time<'a>(&self, fmt: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        self.format(fmt)
+    }
+}
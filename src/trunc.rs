@@ -0,0 +1,25 @@
+//! How `format_trunc!` shortens its formatted `String`
+
+use alloc::string::String;
+
+/// Keeps `s` if it has at most `max` chars, and otherwise cuts it so that it
+/// ends in `ellipsis` and has exactly `max` chars (an ellipsis longer than that
+/// is cut itself)
+pub fn truncate(mut s: String, max: usize, ellipsis: &str) -> String {
+    // byte offset of the char at `n`, if `text` is longer than `n` chars
+    let char_end = |text: &str, n: usize| text.char_indices().nth(n).map(|(idx, _)| idx);
+
+    if char_end(&s, max).is_none() {
+        return s;
+    }
+    let ellipsis = match char_end(ellipsis, max) {
+        Some(end) => &ellipsis[..end],
+        None => ellipsis,
+    };
+    let keep = max - ellipsis.chars().count();
+    if let Some(end) = char_end(&s, keep) {
+        s.truncate(end);
+    }
+    s.push_str(ellipsis);
+    s
+}
@@ -0,0 +1,132 @@
+//! Fallible formatting for `try_format!`: a value implementing [`TryDisplay`]
+//! may fail to render, and the first failure is returned instead of the `String`
+
+use alloc::string::{String, ToString};
+use core::cell::Cell;
+use core::fmt;
+
+/// `Display` that can fail with a [`FormatError`], e.g. for a value that has to
+/// be looked up or validated while it is rendered. `try_format!` uses it in
+/// place of `Display` for every placeholder whose value implements it.
+pub trait TryDisplay {
+    /// Render `self` into `f` like [`fmt::Display::fmt`], failing with the
+    /// [`FormatError`] that `try_format!` should return
+    fn try_fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), FormatError>;
+}
+
+/// Why `try_format!` failed: the error of the first [`TryDisplay`] value that
+/// failed, or a `Display` implementation returning [`fmt::Error`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    message: String,
+}
+
+impl FormatError {
+    /// An error with `message` as its `Display` output
+    pub fn new(message: impl fmt::Display) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+
+    /// The message given to [`FormatError::new`]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl core::error::Error for FormatError {}
+
+/// So `write!(f, ..)?` works inside [`TryDisplay::try_fmt`]
+impl From<fmt::Error> for FormatError {
+    fn from(_: fmt::Error) -> Self {
+        Self::new("a formatting trait implementation returned an error")
+    }
+}
+
+/// Holds the failure that aborted the formatting, if any
+#[derive(Default)]
+pub struct Failure(Cell<Option<FormatError>>);
+
+impl Failure {
+    pub fn finish(self, result: fmt::Result, out: String) -> Result<String, FormatError> {
+        match (self.0.into_inner(), result) {
+            (Some(err), _) => Err(err),
+            (None, Ok(())) => Ok(out),
+            (None, Err(err)) => Err(err.into()),
+        }
+    }
+}
+
+pub struct TryArg<'a, T: ?Sized> {
+    value: &'a T,
+    failure: &'a Failure,
+}
+
+impl<'a, T: ?Sized> TryArg<'a, T> {
+    pub fn new(value: &'a T, failure: &'a Failure) -> Self {
+        Self { value, failure }
+    }
+}
+
+/// Preferred: taken when `T: TryDisplay`, as it matches `&TryArg` without autoref
+pub trait ViaTryDisplay<'a, T: ?Sized> {
+    fn try_arg(&self) -> Fallible<'a, T>;
+}
+
+impl<'a, T: TryDisplay + ?Sized> ViaTryDisplay<'a, T> for TryArg<'a, T> {
+    fn try_arg(&self) -> Fallible<'a, T> {
+        Fallible {
+            value: self.value,
+            failure: self.failure,
+        }
+    }
+}
+
+/// Fallback: the value itself, formatted as usual
+pub trait ViaDisplay<'a, T: ?Sized> {
+    fn try_arg(&self) -> &'a T;
+}
+
+impl<'a, T: ?Sized> ViaDisplay<'a, T> for &TryArg<'a, T> {
+    fn try_arg(&self) -> &'a T {
+        self.value
+    }
+}
+
+/// Renders with [`TryDisplay`], recording a failure and aborting the write
+pub struct Fallible<'a, T: ?Sized> {
+    value: &'a T,
+    failure: &'a Failure,
+}
+
+impl<T: TryDisplay + ?Sized> fmt::Display for Fallible<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.try_fmt(f).map_err(|err| {
+            self.failure.0.set(Some(err));
+            fmt::Error
+        })
+    }
+}
+
+/// Every other trait is the value's own, as [`TryDisplay`] only replaces
+/// `Display`: `{v:x}` formats like it does outside `try_format!`
+macro_rules! forward_fmt {
+    ($($trait:ident),*) => {$(
+        impl<T: fmt::$trait + ?Sized> fmt::$trait for Fallible<'_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::$trait::fmt(self.value, f)
+            }
+        }
+    )*};
+}
+
+forward_fmt!(
+    Debug, LowerHex, UpperHex, Octal, Binary, LowerExp, UpperExp, Pointer
+);
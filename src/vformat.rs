@@ -0,0 +1,93 @@
+use core::fmt::{self, Display};
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::borrow::Borrow;
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// A table of named values that [`vformat!`](crate::vformat) placeholders are resolved from
+///
+/// Implemented for slices, arrays and `Vec`s of `(name, value)` pairs, and for
+/// `BTreeMap`/`HashMap` keyed by anything that borrows as `str`.
+pub trait ValueTable {
+    /// Look up the value registered under `name`
+    fn lookup(&self, name: &str) -> Option<&dyn Display>;
+}
+
+impl<T: ValueTable + ?Sized> ValueTable for &T {
+    fn lookup(&self, name: &str) -> Option<&dyn Display> {
+        (**self).lookup(name)
+    }
+}
+
+impl<K: AsRef<str>, V: Display> ValueTable for [(K, V)] {
+    fn lookup(&self, name: &str) -> Option<&dyn Display> {
+        self.iter()
+            .find(|(key, _)| key.as_ref() == name)
+            .map(|(_, value)| value as &dyn Display)
+    }
+}
+
+impl<K: AsRef<str>, V: Display, const N: usize> ValueTable for [(K, V); N] {
+    fn lookup(&self, name: &str) -> Option<&dyn Display> {
+        self.as_slice().lookup(name)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: AsRef<str>, V: Display> ValueTable for Vec<(K, V)> {
+    fn lookup(&self, name: &str) -> Option<&dyn Display> {
+        self.as_slice().lookup(name)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Borrow<str> + Ord, V: Display> ValueTable for BTreeMap<K, V> {
+    fn lookup(&self, name: &str) -> Option<&dyn Display> {
+        self.get(name).map(|value| value as &dyn Display)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Borrow<str> + Eq + Hash, V: Display, S: BuildHasher> ValueTable for HashMap<K, V, S> {
+    fn lookup(&self, name: &str) -> Option<&dyn Display> {
+        self.get(name).map(|value| value as &dyn Display)
+    }
+}
+
+/// Error returned by [`vformat!`](crate::vformat) when a placeholder has no entry in the table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingValue {
+    name: &'static str,
+}
+
+impl MissingValue {
+    /// The placeholder name that could not be resolved
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl Display for MissingValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no value named `{}` in vformat! table", self.name)
+    }
+}
+
+impl core::error::Error for MissingValue {}
+
+/// Resolve every placeholder name up front, failing on the first missing one
+pub fn resolve<'t, T: ValueTable + ?Sized, const N: usize>(
+    table: &'t T,
+    names: [&'static str; N],
+) -> Result<[&'t dyn Display; N], MissingValue> {
+    let mut values: [&dyn Display; N] = [&""; N];
+    for (slot, name) in values.iter_mut().zip(names) {
+        *slot = table.lookup(name).ok_or(MissingValue { name })?;
+    }
+    Ok(values)
+}
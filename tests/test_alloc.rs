@@ -0,0 +1,43 @@
+#![no_std]
+#![cfg(feature = "alloc")]
+// Exercises the allocating macros from a `no_std` crate, where the generated
+// code can't reach `std`
+
+extern crate alloc;
+
+mod test_alloc {
+    use alloc::borrow::Cow;
+    use alloc::boxed::Box;
+    use alloc::string::String;
+    use alloc::sync::Arc;
+    use formati::{
+        format, format_arc, format_boxed, format_cow, format_html, format_sep, format_trunc,
+        try_format,
+    };
+
+    #[test]
+    fn test_alloc_format() {
+        let point = (3, 4);
+
+        assert_eq!(format!("({point.0}, {point.1:>2})"), "(3,  4)");
+        assert_eq!(try_format!("{point.0}-{point.1}"), Ok(String::from("3-4")));
+        assert_eq!(format_sep!(", ", point.0, point.1), "3, 4");
+        assert_eq!(
+            format_trunc!(3, "", "{point.0}{point.1}{point.0}{point.1}"),
+            "343"
+        );
+        assert_eq!(format_html!("<i>{point.0}</i>"), "<i>3</i>");
+    }
+
+    #[test]
+    fn test_alloc_targets() {
+        let point = (3, 4);
+
+        let cow: Cow<'static, str> = format_cow!("{point.0}");
+        assert_eq!(cow, "3");
+        let arc: Arc<str> = format_arc!("{point.1}");
+        assert_eq!(&*arc, "4");
+        let boxed: Box<str> = format_boxed!("{point.0}{point.1}");
+        assert_eq!(&*boxed, "34");
+    }
+}
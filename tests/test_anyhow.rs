@@ -1,6 +1,6 @@
 #[cfg(feature = "anyhow")]
 mod test_anyhow {
-    use formati::{anyhow, bail};
+    use formati::{anyhow, bail, bail_at, ensure};
 
     #[test]
     fn test_anyhow_basic() {
@@ -109,4 +109,137 @@ mod test_anyhow {
         let result = validate_user(&active_user);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_bail_at_location() {
+        struct User {
+            id: u32,
+            name: String,
+        }
+
+        fn validate_user(user: &User) -> anyhow::Result<()> {
+            if user.id == 0 {
+                bail_at!("User {user.name} has no ID ({user.name.len()} chars)");
+            }
+            Ok(())
+        }
+
+        let line = line!() - 5;
+        let err = validate_user(&User {
+            id: 0,
+            name: String::from("Alice"),
+        })
+        .unwrap_err();
+
+        let location = format!("{}:{}", file!(), line);
+        let chain: Vec<String> = err.chain().map(ToString::to_string).collect();
+        assert_eq!(chain, [location.as_str(), "User Alice has no ID (5 chars)"]);
+        assert_eq!(err.to_string(), location);
+        assert_eq!(
+            format!("{err:#}"),
+            format!("{location}: User Alice has no ID (5 chars)")
+        );
+    }
+
+    #[test]
+    fn test_ensure_message_is_lazy() {
+        use std::cell::Cell;
+
+        struct Account {
+            owner: &'static str,
+            balance: u32,
+            lookups: Cell<u32>,
+        }
+
+        impl Account {
+            fn owner(&self) -> &str {
+                self.lookups.set(self.lookups.get() + 1);
+                self.owner
+            }
+        }
+
+        fn withdraw(account: &Account, amount: u32) -> anyhow::Result<u32> {
+            ensure!(
+                account.balance >= amount,
+                "{account.owner()} has {account.balance}, {account.owner()} needs {amount}"
+            );
+            Ok(account.balance - amount)
+        }
+
+        let account = Account {
+            owner: "alice",
+            balance: 30,
+            lookups: Cell::new(0),
+        };
+
+        assert_eq!(withdraw(&account, 10).unwrap(), 20);
+        assert_eq!(account.lookups.get(), 0);
+
+        assert_eq!(
+            withdraw(&account, 50).unwrap_err().to_string(),
+            "alice has 30, alice needs 50"
+        );
+        assert_eq!(account.lookups.get(), 1);
+    }
+
+    #[test]
+    fn test_anyhow_question_mark_mid_chain() {
+        use formati::format;
+        use std::cell::Cell;
+
+        struct Record {
+            name: String,
+        }
+
+        impl Record {
+            fn normalize(&self) -> Record {
+                Record {
+                    name: self.name.trim().to_lowercase(),
+                }
+            }
+        }
+
+        struct Parser {
+            parses: Cell<u32>,
+        }
+
+        impl Parser {
+            fn parse(&self, s: &str) -> anyhow::Result<Record> {
+                self.parses.set(self.parses.get() + 1);
+                match s.strip_prefix("name=") {
+                    Some(name) => Ok(Record {
+                        name: name.to_string(),
+                    }),
+                    None => Err(anyhow!("no name in {s:?}")),
+                }
+            }
+        }
+
+        fn describe(parser: &Parser, s: &str) -> anyhow::Result<String> {
+            let out = format!(
+                "{parser.parse(s)?.normalize().name} ({parser.parse(s)?.normalize().name.len()} chars) / {parser.parse(s)?.normalize().name:>8}"
+            );
+            // only reached when every `?` above succeeded
+            parser.parses.set(parser.parses.get() + 100);
+            Ok(out)
+        }
+
+        let parser = Parser {
+            parses: Cell::new(0),
+        };
+
+        assert_eq!(
+            describe(&parser, "name= Ada ").unwrap(),
+            "ada (3 chars) /      ada"
+        );
+        // `{..name}` is parsed once and shared by both placeholders holding it;
+        // `{..name.len()}` is its own expression
+        assert_eq!(parser.parses.get(), 2 + 100);
+
+        parser.parses.set(0);
+        let err = describe(&parser, "id=7").unwrap_err();
+        assert_eq!(err.to_string(), "no name in \"id=7\"");
+        // the first `?` returned before anything else was evaluated
+        assert_eq!(parser.parses.get(), 1);
+    }
 }
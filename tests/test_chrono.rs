@@ -0,0 +1,54 @@
+#[cfg(feature = "chrono")]
+mod test_chrono {
+    use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+    use formati::format;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_time_spec_system_time() {
+        // 2024-03-09T14:05:07Z
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_709_993_107);
+
+        assert_eq!(format!("[{now:time(%H:%M:%S)}]"), "[14:05:07]");
+        assert_eq!(
+            format!("{now:time(%Y-%m-%d)} {now:time(%H:%M)}"),
+            "2024-03-09 14:05"
+        );
+    }
+
+    #[test]
+    fn test_time_spec_chrono_values() {
+        struct Event {
+            at: chrono::DateTime<Utc>,
+        }
+
+        let event = Event {
+            at: Utc.with_ymd_and_hms(2024, 3, 9, 14, 5, 7).unwrap(),
+        };
+        let local = event
+            .at
+            .with_timezone(&FixedOffset::east_opt(2 * 3600).unwrap());
+        let day = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+
+        assert_eq!(format!("{event.at:time(%d/%m %H:%M:%S)}"), "09/03 14:05:07");
+        assert_eq!(format!("{local:time(%H:%M %:z)}"), "16:05 +02:00");
+        assert_eq!(
+            format!("{day:time(%A)} {day.succ_opt().unwrap():time(%a)}"),
+            "Saturday Sun"
+        );
+
+        // the directive is part of the dedup key; the plain placeholder is separate
+        let (s, audit) =
+            formati::format_audit!("{event.at:time(%H)}h {event.at:time(%H)}h {event.at}");
+        assert_eq!(s, "14h 14h 2024-03-09 14:05:07 UTC");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (3, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "a formatting trait implementation returned an error")]
+    fn test_time_spec_missing_field_panics() {
+        // a date has no hour, which chrono only finds out while formatting
+        let day = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let _ = format!("{day:time(%H)}");
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(feature = "color")]
+mod test_color {
+    use formati::format;
+
+    struct User {
+        name: &'static str,
+        unread: u32,
+    }
+
+    #[test]
+    fn test_color_single_style() {
+        let user = User {
+            name: "ada",
+            unread: 3,
+        };
+
+        assert_eq!(format!("{user.name:red}"), "\x1b[31mada\x1b[0m");
+        assert_eq!(
+            format!("hi {user.name:green}, {user.unread:bold} new"),
+            "hi \x1b[32mada\x1b[0m, \x1b[1m3\x1b[0m new"
+        );
+    }
+
+    #[test]
+    fn test_color_combined_styles() {
+        let user = User {
+            name: "ada",
+            unread: 12,
+        };
+        let count = user.unread;
+
+        assert_eq!(format!("{user.name:red.bold}"), "\x1b[31;1mada\x1b[0m");
+        assert_eq!(
+            format!("{count:underline.cyan.dim}"),
+            "\x1b[4;36;2m12\x1b[0m"
+        );
+
+        // styled and plain placeholders of one expression are still one evaluation
+        let (s, audit) = formati::format_audit!("{user.name:blue}/{user.name}/{user.name:blue}");
+        assert_eq!(s, "\x1b[34mada\x1b[0m/ada/\x1b[34mada\x1b[0m");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (3, 2));
+    }
+}
@@ -0,0 +1,32 @@
+mod test_const_format {
+    use formati::const_format;
+
+    const NOT_FOUND: &str = const_format!("E{404}: {\"not found\"}");
+    static LIMITS: &str = const_format!("range {-1}..={25}, strict={true}, sep={'|'}");
+
+    #[test]
+    fn test_const_format_items() {
+        assert_eq!(NOT_FOUND, "E404: not found");
+        assert_eq!(LIMITS, "range -1..=25, strict=true, sep=|");
+    }
+
+    #[test]
+    fn test_const_format_text_only() {
+        const PLAIN: &str = const_format!("no placeholders");
+        const EMPTY: &str = const_format!("");
+        const BRACES: &str = const_format!("{{literal}} {1}{2} é");
+
+        assert_eq!(PLAIN, "no placeholders");
+        assert_eq!(EMPTY, "");
+        assert_eq!(BRACES, "{literal} 12 é");
+    }
+
+    #[test]
+    fn test_const_format_in_const_context() {
+        const MSG: &str = const_format!("max {255} retries");
+        const LEN: usize = MSG.len();
+
+        assert_eq!(LEN, 15);
+        assert!(matches!(MSG.as_bytes(), [b'm', .., b's']));
+    }
+}
@@ -0,0 +1,234 @@
+#![no_std]
+#![cfg(feature = "core-macros")]
+// Exercises the `core-macros` wrappers from a `no_std` crate, without `stdio`
+
+mod test_core {
+    use core::cell::Cell;
+    use formati::{
+        assert, assert_eq, assert_ne, debug_assert, debug_assert_eq, debug_assert_ne, panic, todo,
+        unimplemented, unreachable,
+    };
+
+    struct Sensor {
+        id: u32,
+        reading: i32,
+    }
+
+    impl Sensor {
+        fn scaled(&self) -> i32 {
+            self.reading * 10
+        }
+    }
+
+    const SENSOR: Sensor = Sensor { id: 7, reading: 4 };
+
+    #[test]
+    #[should_panic(expected = "sensor 7 failed with reading 4")]
+    fn test_core_panic() {
+        let sensor = SENSOR;
+        panic!("sensor {sensor.id} failed with reading {sensor.reading}");
+    }
+
+    #[test]
+    #[should_panic(expected = "explicit panic")]
+    fn test_core_panic_without_message() {
+        panic!();
+    }
+
+    #[test]
+    #[should_panic(expected = "internal error: entered unreachable code: sensor 7 scaled to 40")]
+    fn test_core_unreachable() {
+        let sensor = SENSOR;
+        unreachable!("sensor {sensor.id} scaled to {sensor.scaled()}");
+    }
+
+    #[test]
+    #[should_panic(expected = "not yet implemented: calibrate sensor 7")]
+    fn test_core_todo() {
+        let sensor = SENSOR;
+        todo!("calibrate sensor {sensor.id}");
+    }
+
+    #[test]
+    #[should_panic(expected = "not implemented: reset for sensor 7")]
+    fn test_core_unimplemented() {
+        let sensor = SENSOR;
+        unimplemented!("reset for sensor {sensor.id}");
+    }
+
+    #[test]
+    fn test_core_assert_passes() {
+        let sensor = SENSOR;
+        assert!(sensor.reading > 0);
+        assert!(sensor.reading > 0, "sensor {sensor.id} is negative");
+        assert_eq!(sensor.scaled(), 40, "sensor {sensor.id} scaled wrong");
+        assert_ne!(sensor.id, 0, "sensor {sensor.id} has no id",);
+    }
+
+    // the message is formatted inside the failure branch only, so nothing in
+    // it ran for the passing assertions and the failing one runs it once
+    #[test]
+    #[should_panic(expected = "formatted [1] time(s)")]
+    fn test_core_assert_message_is_lazy() {
+        let calls = Cell::new(0);
+        let tick = || {
+            calls.set(calls.get() + 1);
+            calls.get()
+        };
+
+        assert!(calls.get() == 0, "{tick()}");
+        assert_eq!(calls.get(), 0, "{tick()}");
+        assert_ne!(calls.get(), 1, "{tick()}");
+        assert!(calls.get() > 0, "formatted [{tick()}] time(s)");
+    }
+
+    #[test]
+    #[should_panic(expected = "sensor 7 reading 4 is too low")]
+    fn test_core_assert_message() {
+        let sensor = SENSOR;
+        assert!(
+            sensor.reading > 5,
+            "sensor {sensor.id} reading {sensor.reading} is too low"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: sensor.reading > 5 (left = 4, right = 5)")]
+    fn test_core_assert_comparison_operands() {
+        let sensor = SENSOR;
+        assert!(sensor.reading > 5);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion failed: sensor.scaled() == sensor.id as i32 * 5 (left = 40, right = 35)"
+    )]
+    fn test_core_assert_comparison_expressions() {
+        let sensor = SENSOR;
+        assert!(sensor.id != 0 && sensor.reading > 0);
+        assert!(sensor.scaled() == sensor.id as i32 * 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: a == b (left = _, right = _)")]
+    fn test_core_assert_comparison_without_debug() {
+        #[derive(PartialEq)]
+        struct Opaque(u8);
+
+        let (a, b) = (Opaque(1), Opaque(2));
+        assert!(a == b);
+    }
+
+    #[test]
+    #[should_panic(expected = "sensor 7 scaled to 40, expected 41")]
+    fn test_core_assert_eq_message() {
+        let sensor = SENSOR;
+        let expected = 41;
+        assert_eq!(
+            sensor.scaled(),
+            expected,
+            "sensor {sensor.id} scaled to {sensor.scaled()}, expected {expected}"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "sensor 7 must not read 4")]
+    fn test_core_assert_ne_message() {
+        let sensor = SENSOR;
+        assert_ne!(
+            sensor.reading, 4,
+            "sensor {sensor.id} must not read {sensor.reading}"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "sensor 7 must not read 4")]
+    fn test_core_assert_ne_positional_message() {
+        let sensor = SENSOR;
+        assert_ne!(
+            sensor.reading, 4,
+            "sensor {sensor.id} must not read {}",
+            sensor.reading
+        );
+    }
+
+    #[test]
+    #[cfg_attr(
+        debug_assertions,
+        should_panic(expected = "sensor 7 scaled to 40, expected 41")
+    )]
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    fn test_core_debug_assert_eq_message() {
+        let sensor = SENSOR;
+        let expected = 41;
+        debug_assert_eq!(
+            sensor.scaled(),
+            expected,
+            "sensor {sensor.id} scaled to {sensor.scaled()}, expected {expected}"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "sensor 7 must not read 4"))]
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    fn test_core_debug_assert_ne_message() {
+        let sensor = SENSOR;
+        debug_assert_ne!(
+            sensor.reading, 4,
+            "sensor {sensor.id} must not read {sensor.reading}"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "sensor 7 reads 4, below 5"))]
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    fn test_core_debug_assert_message() {
+        let sensor = SENSOR;
+        debug_assert!(
+            sensor.reading >= 5,
+            "sensor {sensor.id} reads {sensor.reading}, below 5"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    fn test_core_debug_assert_side_effects() {
+        let calls = Cell::new(0);
+        let tick = || {
+            calls.set(calls.get() + 1);
+            calls.get()
+        };
+
+        // a passing assertion never formats its message
+        debug_assert_eq!(1, 1, "{tick()}");
+        debug_assert!(true, "{tick()}");
+        assert_eq!(calls.get(), 0);
+
+        // in release the operands aren't evaluated either
+        debug_assert_ne!(tick(), 0, "{tick()}");
+        let expected = if cfg!(debug_assertions) { 1 } else { 0 };
+        assert_eq!(calls.get(), expected);
+    }
+
+    // `cargo test-core-release`: a failing assertion neither panics nor formats.
+    // Nothing in it is even compiled, so `tick` is unused
+    #[test]
+    #[cfg(not(debug_assertions))]
+    #[allow(unused_variables)]
+    fn test_core_debug_assert_release_skips_message() {
+        let calls = Cell::new(0);
+        let tick = || {
+            calls.set(calls.get() + 1);
+            calls.get()
+        };
+
+        debug_assert!(tick() > 100, "{tick()} then {tick()}");
+        debug_assert_eq!(tick(), 100, "{tick()}");
+        debug_assert_ne!(tick(), tick(), "{tick()}");
+        assert_eq!(calls.get(), 0);
+
+        // nor type-checked
+        debug_assert!(calls.get() == "none", "{calls.no_such_method()}");
+        debug_assert_eq!(tick(), (), "{tick().no_such_field}");
+    }
+}
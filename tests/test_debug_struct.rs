@@ -0,0 +1,126 @@
+mod test_debug_struct {
+    use formati::debug_struct;
+    use std::fmt;
+
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    struct Segment {
+        start: Point,
+        end: Point,
+        label: String,
+    }
+
+    impl Segment {
+        fn length_sq(&self) -> i32 {
+            let dx = self.end.x - self.start.x;
+            let dy = self.end.y - self.start.y;
+            dx * dx + dy * dy
+        }
+    }
+
+    impl fmt::Debug for Point {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            debug_struct!(f, "Point", x = self.x, y = self.y)
+        }
+    }
+
+    impl fmt::Debug for Segment {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            debug_struct!(
+                f,
+                "Segment",
+                start = self.start,
+                end = self.end,
+                label = self.label,
+                length_sq = self.length_sq(),
+                span = "{self.start.x}..{self.end.x}",
+            )
+        }
+    }
+
+    /// The same output written by hand, to compare against
+    struct HandWritten<'a>(&'a Segment);
+
+    impl fmt::Debug for HandWritten<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let segment = self.0;
+            f.debug_struct("Segment")
+                .field("start", &segment.start)
+                .field("end", &segment.end)
+                .field("label", &segment.label)
+                .field("length_sq", &segment.length_sq())
+                .field(
+                    "span",
+                    &format_args!("{}..{}", segment.start.x, segment.end.x),
+                )
+                .finish()
+        }
+    }
+
+    fn segment() -> Segment {
+        Segment {
+            start: Point { x: 1, y: 2 },
+            end: Point { x: 4, y: 6 },
+            label: "diagonal".into(),
+        }
+    }
+
+    #[test]
+    fn test_debug_struct_simple() {
+        let point = Point { x: 1, y: -2 };
+        assert_eq!(format!("{point:?}"), "Point { x: 1, y: -2 }");
+    }
+
+    #[test]
+    fn test_debug_struct_matches_hand_written() {
+        let segment = segment();
+        assert_eq!(
+            format!("{:?}", segment),
+            format!("{:?}", HandWritten(&segment))
+        );
+        assert_eq!(
+            format!("{segment:?}"),
+            "Segment { start: Point { x: 1, y: 2 }, end: Point { x: 4, y: 6 }, \
+             label: \"diagonal\", length_sq: 25, span: 1..4 }"
+        );
+    }
+
+    #[test]
+    fn test_debug_struct_alternate() {
+        let segment = segment();
+        assert_eq!(
+            format!("{:#?}", segment),
+            format!("{:#?}", HandWritten(&segment))
+        );
+        assert_eq!(
+            format!("{:#?}", segment.start),
+            "Point {\n    x: 1,\n    y: 2,\n}"
+        );
+    }
+
+    #[test]
+    fn test_debug_struct_no_fields_and_raw_names() {
+        struct Empty;
+        struct Keyword {
+            kind: u8,
+        }
+
+        impl fmt::Debug for Empty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                debug_struct!(f, "Empty")
+            }
+        }
+
+        impl fmt::Debug for Keyword {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                debug_struct!(f, "Keyword", r#type = self.kind)
+            }
+        }
+
+        assert_eq!(format!("{:?}", Empty), "Empty");
+        assert_eq!(format!("{:?}", Keyword { kind: 3 }), "Keyword { type: 3 }");
+    }
+}
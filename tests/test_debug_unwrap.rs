@@ -0,0 +1,64 @@
+mod test_debug_unwrap {
+    use formati::debug_unwrap;
+    use std::panic;
+
+    struct Case {
+        name: &'static str,
+        input: &'static str,
+    }
+
+    fn panic_message(f: impl FnOnce() + panic::UnwindSafe) -> String {
+        let payload = panic::catch_unwind(f).unwrap_err();
+        match payload.downcast::<String>() {
+            Ok(msg) => *msg,
+            Err(payload) => payload.downcast::<&str>().unwrap().to_string(),
+        }
+    }
+
+    #[test]
+    fn test_debug_unwrap_ok() {
+        let case = Case {
+            name: "port",
+            input: "8080",
+        };
+
+        let port: u16 = debug_unwrap!(case.input.parse::<u16>(), "case {case.name}");
+        assert_eq!(port, 8080);
+    }
+
+    #[test]
+    fn test_debug_unwrap_err() {
+        let case = Case {
+            name: "port",
+            input: "80a",
+        };
+
+        let msg = panic_message(|| {
+            let _: u16 = debug_unwrap!(
+                case.input.parse::<u16>(),
+                "case {case.name} ({case.input.len()} chars)"
+            );
+        });
+        assert_eq!(
+            msg,
+            "case port (3 chars): ParseIntError { kind: InvalidDigit }"
+        );
+    }
+
+    #[test]
+    fn test_debug_unwrap_err_binding() {
+        let case = Case {
+            name: "empty",
+            input: "",
+        };
+
+        // the error is bound as `err`, so the context can format it too
+        let msg = panic_message(|| {
+            let _: u16 = debug_unwrap!(case.input.parse::<u16>(), "{case.name}: {err}");
+        });
+        assert_eq!(
+            msg,
+            "empty: cannot parse integer from empty string: ParseIntError { kind: Empty }"
+        );
+    }
+}
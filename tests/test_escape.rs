@@ -0,0 +1,88 @@
+#![cfg(feature = "alloc")]
+mod test_escape {
+    use formati::{format_html, format_shell};
+    use std::cell::Cell;
+
+    struct User {
+        name: String,
+        bio: &'static str,
+        renders: Cell<u32>,
+    }
+
+    impl User {
+        fn display_name(&self) -> &str {
+            self.renders.set(self.renders.get() + 1);
+            &self.name
+        }
+    }
+
+    fn user() -> User {
+        User {
+            name: String::from("<Tom & \"Jerry\">"),
+            bio: "it's <b>bold</b>",
+            renders: Cell::new(0),
+        }
+    }
+
+    #[test]
+    fn test_format_html_escapes_values_only() {
+        let user = user();
+
+        let s = format_html!("<b class=\"name\">{user.name}</b> & <i>{user.bio}</i>");
+        assert_eq!(
+            s,
+            "<b class=\"name\">&lt;Tom &amp; &quot;Jerry&quot;&gt;</b> & <i>it&#39;s &lt;b&gt;bold&lt;/b&gt;</i>"
+        );
+
+        // braces in the template stay literal; plain values pass through
+        assert_eq!(
+            format_html!("{{{user.bio.len()}}} <br> {user.name.is_empty()}"),
+            "{16} <br> false"
+        );
+    }
+
+    #[test]
+    fn test_format_html_specs_and_dedup() {
+        let user = user();
+
+        // the spec applies before escaping, and the value is bound once
+        assert_eq!(
+            format_html!("[{user.display_name():?}] [{user.display_name()}]"),
+            "[&quot;&lt;Tom &amp; \\&quot;Jerry\\&quot;&gt;&quot;] [&lt;Tom &amp; &quot;Jerry&quot;&gt;]"
+        );
+        assert_eq!(user.renders.get(), 1);
+
+        let price = 9.5;
+        assert_eq!(
+            format_html!("<td>{price:>6.2}</td><td>{tag}</td>", tag = "a&b"),
+            "<td>  9.50</td><td>a&amp;b</td>"
+        );
+    }
+
+    #[test]
+    fn test_format_shell_quotes_values_only() {
+        let user = user();
+        let file = ("notes.txt", "my notes; rm -rf ~", "");
+
+        assert_eq!(
+            format_shell!("cat {file.0} | grep -v '#' > {file.1}"),
+            "cat notes.txt | grep -v '#' > 'my notes; rm -rf ~'"
+        );
+        assert_eq!(format_shell!("touch {file.2}"), "touch ''");
+        assert_eq!(
+            format_shell!("echo {user.bio}"),
+            r"echo 'it'\''s <b>bold</b>'"
+        );
+        assert_eq!(
+            format_shell!("echo {user.name}"),
+            "echo '<Tom & \"Jerry\">'"
+        );
+
+        let args = ["$(reboot)", "`id`", "a b", "--flag=x/y"];
+        assert_eq!(
+            format_shell!("run {args[0]} {args[1]} {args[2]} {args[3]}"),
+            "run '$(reboot)' '`id`' 'a b' --flag=x/y"
+        );
+        assert_eq!(format_shell!("sleep {secs:03}", secs = 5), "sleep 005");
+    }
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "alloc")]
+mod test_fmt_line {
+    use formati::{fmt_line, format_args};
+    use std::cell::RefCell;
+    use std::fmt;
+
+    /// A minimal custom sink in the shape of `log::Log`: it receives the
+    /// message as `fmt::Arguments` and keeps the rendered lines
+    #[derive(Default)]
+    struct CaptureLogger {
+        lines: RefCell<Vec<String>>,
+    }
+
+    impl CaptureLogger {
+        fn log(&self, target: &str, args: fmt::Arguments<'_>) {
+            self.lines
+                .borrow_mut()
+                .push(format!("{target}: {}", fmt_line(args)));
+        }
+    }
+
+    struct Session {
+        user: (&'static str, u32),
+        requests: Vec<u32>,
+    }
+
+    #[test]
+    fn test_fmt_line_custom_logger() {
+        let logger = CaptureLogger::default();
+        let session = Session {
+            user: ("alice", 7),
+            requests: vec![120, 80],
+        };
+
+        logger.log(
+            "auth",
+            format_args!("{session.user.0} (#{session.user.1}) signed in"),
+        );
+        logger.log(
+            "http",
+            format_args!(
+                "{session.requests.len()} requests, {session.requests.iter().sum::<u32>():>5}ms total, first {}ms",
+                session.requests[0]
+            ),
+        );
+        logger.log("plain", format_args!("no placeholders {{here}}"));
+
+        assert_eq!(
+            *logger.lines.borrow(),
+            [
+                "auth: alice (#7) signed in",
+                "http: 2 requests,   200ms total, first 120ms",
+                "plain: no placeholders {here}",
+            ]
+        );
+    }
+}
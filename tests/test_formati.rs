@@ -1,3 +1,7 @@
+// `strict` spans template expressions at the literal, so clippy lints them as user code
+#![cfg_attr(feature = "strict", allow(clippy::redundant_closure_call))]
+#![cfg(feature = "alloc")]
+
 mod test_formati {
     use formati::format;
     use std::f32::consts;
@@ -72,6 +76,20 @@ mod test_formati {
         assert_eq!(result, "{escaped} but interpolated");
     }
 
+    #[test]
+    fn test_formati_trivial_templates() {
+        assert_eq!(format!(""), "");
+        assert_eq!(format!("   "), "   ");
+        assert_eq!(format!(" \t\n "), " \t\n ");
+        assert_eq!(format!("",), "");
+
+        // only escaped braces, including consecutive escapes
+        assert_eq!(format!("{{{{}}}}"), "{{}}");
+        assert_eq!(format!("{{}}{{}}"), "{}{}");
+        assert_eq!(format!("}}"), "}");
+        assert_eq!(format!(" {{ }} "), " { } ");
+    }
+
     #[test]
     fn test_formati_struct_fields_and_methods() {
         struct Employee {
@@ -149,6 +167,43 @@ mod test_formati {
         Ok(())
     }
 
+    #[test]
+    fn test_formati_try_operator_dedup() {
+        use std::cell::Cell;
+
+        struct Account {
+            owner: &'static str,
+            balance: i64,
+        }
+
+        let lookups = Cell::new(0);
+        let lookup = |id: u32| -> Result<Account, String> {
+            lookups.set(lookups.get() + 1);
+            match id {
+                1 => Ok(Account {
+                    owner: "ann",
+                    balance: 40,
+                }),
+                _ => Err(std::format!("no account {id}")),
+            }
+        };
+
+        let describe = |id: u32| -> Result<String, String> {
+            Ok(format!(
+                "{lookup(id)?.owner}: {lookup(id)?.balance} ({lookup(id)?.owner})"
+            ))
+        };
+
+        // the repeated fallible head runs (and applies `?`) once
+        assert_eq!(describe(1), Ok("ann: 40 (ann)".to_string()));
+        assert_eq!(lookups.get(), 2);
+
+        // the first error short-circuits the whole format!, before later heads run
+        lookups.set(0);
+        assert_eq!(describe(2), Err("no account 2".to_string()));
+        assert_eq!(lookups.get(), 1);
+    }
+
     #[test]
     fn test_formati_range_expressions() {
         let vec = [1, 2, 3, 4, 5];
@@ -226,4 +281,1895 @@ mod test_formati {
         let result = format!("Longest: {longest(s1, s2)}");
         assert_eq!(result, "Longest: world!");
     }
+
+    #[test]
+    fn test_formati_string_keys_with_braces() {
+        use std::collections::HashMap;
+
+        let config: HashMap<&str, i32> = [
+            ("section{0}", 1),
+            ("close}", 2),
+            ("open{", 3),
+            ("host:port", 4),
+            ("{:?}", 5),
+            ("quote\"}", 6),
+        ]
+        .into_iter()
+        .collect();
+
+        let result = format!(
+            "{config[\"section{0}\"]} {config[\"close}\"]} {config[\"open{\"]} {config[\"host:port\"]}"
+        );
+        assert_eq!(result, "1 2 3 4");
+
+        // spec split happens after the structural expression, not inside the key
+        let result = format!("[{config[\"host:port\"]:>3}] [{config[\"{:?}\"]:03}]");
+        assert_eq!(result, "[  4] [005]");
+
+        // escaped quotes inside the key don't end the string early
+        let result = format!("{config[\"quote\\\"}\"]} and {config[\"close}\"]:?}");
+        assert_eq!(result, "6 and 2");
+    }
+
+    #[test]
+    fn test_formati_char_keys_with_braces() {
+        use std::collections::HashMap;
+
+        let pairs: HashMap<char, char> = [('{', '}'), ('}', '{'), (':', ';')].into_iter().collect();
+
+        let result = format!("{pairs[&'{']}{pairs[&'}']}{pairs[&':']:>2}");
+        assert_eq!(result, "}{ ;");
+    }
+
+    #[test]
+    fn test_formati_immediately_called_closures() {
+        let base = 10;
+        let result = format!("Sum: {(|x: i32| x + base)(5)}");
+        assert_eq!(result, "Sum: 15");
+
+        let name = String::from("Alice");
+        let calls = std::cell::Cell::new(0);
+        let result = format!(
+            "{(|greeting: &str| { calls.set(calls.get() + 1); greeting.to_string() + \" \" + &name })(\"Hi\")} / \
+             {(|greeting: &str| { calls.set(calls.get() + 1); greeting.to_string() + \" \" + &name })(\"Hi\")}"
+        );
+        assert_eq!(result, "Hi Alice / Hi Alice");
+        assert_eq!(calls.get(), 1);
+
+        // closure with multiple params and a spec after the call
+        let scale = 2.5;
+        let result = format!("{(|a: f64, b: f64| (a + b) * scale)(1.0, 2.0):.2}");
+        assert_eq!(result, "7.50");
+    }
+
+    #[test]
+    fn test_formati_or_operators_and_closure_pipes() {
+        let a = false;
+        let b = true;
+        let result = format!("{a || b} {a && b} {!a || !b}");
+        assert_eq!(result, "true false true");
+
+        let x = 0b1010u8;
+        let y = 0b0101u8;
+        let result = format!("{x | y} {x ^ y:#06b} {x & y}");
+        assert_eq!(result, "15 0b1111 0");
+
+        let result = format!("{(|x| x)(1)} {(|| 2)()} {(|x: u8| x | 1)(x)}");
+        assert_eq!(result, "1 2 11");
+
+        // operators written without surrounding spaces
+        let result = format!("{a||b} {a&&b} {x|y} {x+y} {x<<1}");
+        assert_eq!(result, "true false 15 15 20");
+    }
+
+    #[test]
+    fn test_formati_width_precision_combinations() {
+        struct Val {
+            x: f64,
+        }
+        let val = Val { x: 1.23456 };
+        let w = 10;
+        let p = 2;
+
+        // static width and precision
+        assert_eq!(format!("[{val.x:8.3}]"), "[   1.235]");
+        // captured width, static precision
+        assert_eq!(format!("[{val.x:w$.3}]"), "[     1.235]");
+        // named width argument
+        assert_eq!(format!("[{val.x:width$.1}]", width = 6), "[   1.2]");
+        // precision from the preceding positional argument
+        assert_eq!(format!("[{val.x:.*}]", 4), "[1.2346]");
+        assert_eq!(format!("[{val.x:>9.*}]", 1), "[      1.2]");
+        // captured width and precision
+        assert_eq!(format!("[{val.x:w$.p$}]"), "[      1.23]");
+        // positional width/precision arguments by index
+        assert_eq!(format!("[{val.x:1$.0$}]", 1, 7), "[    1.2]");
+        // the same expression with different specs is still evaluated once
+        assert_eq!(
+            format!("{val.x:.1} {val.x:w$.p$} {val.x:.*}", 3),
+            "1.2       1.23 1.235"
+        );
+    }
+
+    #[test]
+    fn test_formati_mixed_with_positional_args() {
+        let point = (3, 4);
+
+        let result = format!("({point.0}, {point.1}) ({}, {})", point.0, point.1);
+        assert_eq!(result, "(3, 4) (3, 4)");
+
+        let result = format!("{1}-{point.0}-{0}", "a", "b");
+        assert_eq!(result, "b-3-a");
+
+        let result = format!("{point.1} {label}: {}", point.0 + point.1, label = "sum");
+        assert_eq!(result, "4 sum: 7");
+    }
+
+    #[test]
+    fn test_formati_braces_inside_extracted_string_literals() {
+        use std::collections::HashMap;
+
+        let map = HashMap::from([("a{b}", 1), ("{0}", 2)]);
+
+        // braces inside an extracted expression's string literal are not placeholders
+        let result = format!("{map.get(\"a{b}\").unwrap()}");
+        assert_eq!(result, "1");
+
+        let result = format!("{map.get(\"{0}\").unwrap()} {}", "x");
+        assert_eq!(result, "2 x");
+
+        let result = format!("[{\"{}\".len()}] {{literal}} {'{'.is_ascii()}");
+        assert_eq!(result, "[2] {literal} true");
+    }
+
+    #[test]
+    fn test_formati_borrowing_display_adapter() {
+        use std::cell::Cell;
+        use std::fmt::{self, Display};
+
+        struct Document {
+            title: String,
+            views: Cell<u32>,
+        }
+
+        struct Title<'a>(&'a str);
+
+        impl Display for Title<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "<{}>", self.0)
+            }
+        }
+
+        impl Document {
+            fn view(&self) -> impl Display + '_ {
+                self.views.set(self.views.get() + 1);
+                Title(&self.title)
+            }
+        }
+
+        let doc = Document {
+            title: "notes".into(),
+            views: Cell::new(0),
+        };
+
+        // both uses share one adapter that borrows `doc` for the whole call
+        let result = format!("{doc.view()} / {doc.view()}");
+        assert_eq!(result, "<notes> / <notes>");
+        assert_eq!(doc.views.get(), 1);
+
+        // an adapter borrowing a temporary local
+        let result =
+            format!("{Document { title: doc.title.to_uppercase(), views: Cell::new(0) }.view()}");
+        assert_eq!(result, "<NOTES>");
+    }
+
+    #[test]
+    fn test_format_arc() {
+        use std::cell::Cell;
+        use std::sync::Arc;
+
+        let calls = Cell::new(0);
+        let next = || {
+            calls.set(calls.get() + 1);
+            calls.get()
+        };
+        let point = (3, 4);
+
+        let label: Arc<str> = formati::format_arc!("({point.0}, {point.1}) #{next()} #{next()}");
+        assert_eq!(&*label, format!("({point.0}, {point.1}) #1 #1"));
+        assert_eq!(calls.get(), 1);
+        assert_eq!(label, Arc::<str>::from(format!("({}, {}) #1 #1", point.0, point.1)));
+        assert_eq!(Arc::strong_count(&label), 1);
+
+        let empty = formati::format_arc!("");
+        assert_eq!(empty, Arc::<str>::from(""));
+    }
+
+    #[test]
+    fn test_format_boxed() {
+        let point = (3, 4);
+
+        let label: Box<str> = formati::format_boxed!("({point.0}, {point.1:>2})");
+        assert_eq!(&*label, format!("({point.0}, {point.1:>2})"));
+        assert_eq!(label.len(), 7);
+
+        let empty = formati::format_boxed!("");
+        assert_eq!(empty, Box::<str>::from(""));
+    }
+
+    #[test]
+    fn test_formati_path_method_chains() {
+        use std::cell::Cell;
+        use std::path::{MAIN_SEPARATOR, Path, PathBuf};
+
+        let lookups = Cell::new(0);
+        let path = PathBuf::from("logs").join("2024").join("app.log");
+        let current = || {
+            lookups.set(lookups.get() + 1);
+            path.as_path()
+        };
+
+        let result = format!(
+            "{current().file_name().unwrap().to_string_lossy()} in {current().parent().unwrap().display()} \
+             ({current().file_name().unwrap().to_string_lossy():>9})"
+        );
+        assert_eq!(
+            result,
+            std::format!("app.log in logs{MAIN_SEPARATOR}2024 (  app.log)")
+        );
+        // each distinct chain is evaluated once
+        assert_eq!(lookups.get(), 2);
+
+        let root = Path::new("data");
+        let result = format!(
+            "{root.join(\"a.txt\").display()} {root.join(\"a.txt\").extension().unwrap().to_str().unwrap()}"
+        );
+        assert_eq!(result, std::format!("data{MAIN_SEPARATOR}a.txt txt"));
+    }
+
+    #[test]
+    fn test_formati_duration_chains_with_specs() {
+        use std::cell::Cell;
+        use std::time::{Duration, Instant};
+
+        let timing = (Duration::from_millis(1500), Duration::from_micros(2_250));
+
+        let result =
+            format!("{timing.0.as_secs_f64():.3}s {timing.1.as_secs_f64() * 1000.0:>7.2}ms");
+        assert_eq!(result, "1.500s    2.25ms");
+
+        let result =
+            format!("{Duration::from_millis(250).as_secs_f32():.1} {timing.0.as_millis():05}");
+        assert_eq!(result, "0.2 01500");
+
+        // the chain before the spec is extracted once, whatever spec follows it
+        let reads = Cell::new(0);
+        let start = Instant::now();
+        let elapsed = || {
+            reads.set(reads.get() + 1);
+            start.elapsed()
+        };
+        let result = format!(
+            "{elapsed().as_secs_f64():.3} {elapsed().as_secs_f64():.3} {elapsed().as_secs_f64():e}"
+        );
+        assert_eq!(reads.get(), 1);
+
+        let parts: Vec<&str> = result.split(' ').collect();
+        assert_eq!(parts[0], parts[1]);
+        let (whole, fraction) = parts[0].split_once('.').unwrap();
+        assert!(whole.chars().all(|c| c.is_ascii_digit()));
+        assert_eq!(fraction.len(), 3);
+        assert!(parts[2].contains('e'));
+    }
+
+    #[test]
+    fn test_uformat_min() {
+        use formati::uformat_min;
+
+        struct Reading {
+            sensor: u32,
+            value: f64,
+        }
+
+        let reading = Reading {
+            sensor: 7,
+            value: 21.5,
+        };
+
+        assert_eq!(
+            uformat_min!("sensor {reading.sensor}: {reading.value} ({reading.sensor})"),
+            "sensor 7: 21.5 (7)"
+        );
+        assert_eq!(uformat_min!("{{raw}} {}", reading.sensor), "{raw} 7");
+    }
+
+    #[test]
+    fn test_formati_explicit_spec_separator() {
+        let a = 3;
+        let b = 5;
+        let words = ["alpha", "beta"];
+
+        // an unspaced `<` reads as a generic, so `:>6` can't be found heuristically
+        assert_eq!(format!("[{a<b ; >6}]"), "[  true]");
+        assert_eq!(format!("[{a<b || b<a;>6}]"), "[  true]");
+        assert_eq!(format!("[{a<b;}]"), "[true]");
+
+        // `;` inside strings, chars and blocks is not a separator
+        assert_eq!(format!("{\"a;b\".len() ; 03}"), "003");
+        assert_eq!(format!("{[';', 'x'].len() ; <3}|"), "2  |");
+        assert_eq!(format!("{(|| { let n = a; n * b })() ; +}"), "+15");
+
+        // paths and turbofish generics
+        assert_eq!(
+            format!("{Vec::<u8>::with_capacity(4).capacity() ; ^5}"),
+            "  4  "
+        );
+        assert_eq!(format!("{words.join(\"::\") ; ?}"), "\"alpha::beta\"");
+
+        // dedup sees the same head either way
+        assert_eq!(format!("{words.len() ; >3}{words.len()}"), "  22");
+
+        // the `:` form is unchanged
+        assert_eq!(format!("{words.len():>3}"), "  2");
+    }
+
+    #[test]
+    fn test_formati_pointer_spec() {
+        struct Buffer {
+            buf: Vec<u8>,
+            len: usize,
+        }
+
+        impl Buffer {
+            fn describe(&self) -> String {
+                format!("{self.buf.as_ptr():p}+{self.len} @ {&self.len:p}")
+            }
+        }
+
+        let value = 42_u32;
+        let pair = (1_u8, 2_u16);
+        let buffer = Buffer {
+            buf: vec![1, 2, 3],
+            len: 3,
+        };
+
+        // references to locals and fields
+        assert_eq!(format!("{&value:p}"), std::format!("{:p}", &value));
+        assert_eq!(format!("{&pair.1:p}"), std::format!("{:p}", &pair.1));
+        assert_eq!(format!("{&pair.1:>20p}"), std::format!("{:>20p}", &pair.1));
+
+        // raw pointers from method calls, including through `self`
+        assert_eq!(
+            format!("{buffer.buf.as_ptr():p}"),
+            std::format!("{:p}", buffer.buf.as_ptr())
+        );
+        assert_eq!(
+            buffer.describe(),
+            std::format!("{:p}+3 @ {:p}", buffer.buf.as_ptr(), &buffer.len)
+        );
+
+        // a dereferenced reference to a reference
+        let reference = &value;
+        let nested = &reference;
+        assert_eq!(format!("{*nested:p}"), std::format!("{:p}", reference));
+
+        // `{:#p}` and write!
+        let mut out = String::new();
+        formati::write!(out, "{&value:#p}").unwrap();
+        assert_eq!(out, std::format!("{:#p}", &value));
+    }
+
+    #[test]
+    fn test_format_audit() {
+        use formati::format_audit;
+
+        let point = (3, 4);
+        let label = "p";
+
+        // 3 extracted placeholders over 2 unique expressions
+        let (s, audit) = format_audit!("{label}: ({point.0}, {point.1}) |{point.0:>3}| {}", 5);
+        assert_eq!(s, "p: (3, 4) |  3| 5");
+        assert_eq!(audit.placeholders(), 3);
+        assert_eq!(audit.unique_expressions(), 2);
+        assert_eq!(audit.saved_evaluations(), 1);
+
+        // nothing to extract
+        let (s, audit) = format_audit!("{label} {{}}");
+        assert_eq!(s, "p {}");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (0, 0));
+    }
+
+    #[test]
+    fn test_formati_reuses_matching_arguments() {
+        use formati::format_audit;
+        use std::cell::Cell;
+
+        struct User {
+            id: u32,
+            name: &'static str,
+        }
+
+        let reads = Cell::new(0);
+        let user = User { id: 7, name: "ann" };
+        let current = || {
+            reads.set(reads.get() + 1);
+            &user
+        };
+
+        // a named argument holding the same expression as a template head
+        let result = format!("{current().id} {extra:>3}", extra = current().id);
+        assert_eq!(result, "7   7");
+        assert_eq!(reads.get(), 1);
+
+        // a positional argument, with a spec on the template side
+        reads.set(0);
+        let result = format!("{} {current().name:?} {current().id}", current().name);
+        assert_eq!(result, "ann \"ann\" 7");
+        assert_eq!(reads.get(), 2);
+
+        // no generated argument is needed for a head the caller already passes
+        let (result, audit) = format_audit!("{user.id}-{user.name}", id = user.id);
+        assert_eq!(result, "7-ann");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (1, 1));
+    }
+
+    #[test]
+    fn test_formati_shift_operators() {
+        struct Shift {
+            amount: u32,
+        }
+
+        let flags = 0b1011_0000_u32;
+        let x = 3_u32;
+        let shift = Shift { amount: 4 };
+        let (a, b, mask) = (0xF0_u32, 4_u32, 0b101_u32);
+
+        assert_eq!(format!("{flags >> 2}"), "44");
+        assert_eq!(format!("{x << shift.amount}"), "48");
+        assert_eq!(format!("{(a >> b) & mask}"), "5");
+
+        // with specs after the shift
+        assert_eq!(format!("{flags >> 4:08b}"), "00001011");
+        assert_eq!(format!("{x << shift.amount:>5}"), "   48");
+        assert_eq!(format!("{(a >> b) & mask:#x}"), "0x5");
+
+        // unspaced shifts
+        assert_eq!(format!("{flags>>4:b}"), "1011");
+        assert_eq!(format!("{x<<2:>4}"), "  12");
+        assert_eq!(format!("{x<<shift.amount>>2:?}"), "12");
+
+        // shifts next to real generics
+        assert_eq!(format!("{Vec::<u32>::from([x << 1]).len() << 2:>3}"), "  4");
+        assert_eq!(format!("{u32::from(x as u8) >> 1:02}"), "01");
+    }
+
+    #[test]
+    fn test_formati_tuple_index_chains() {
+        use std::cell::Cell;
+
+        struct Inner {
+            name: &'static str,
+            calls: Cell<u32>,
+        }
+
+        impl Inner {
+            fn method(&self) -> usize {
+                self.calls.set(self.calls.get() + 1);
+                self.name.len()
+            }
+        }
+
+        struct Wrapper(Inner, u8);
+
+        struct Holder {
+            pair: (u8, &'static str),
+        }
+
+        let wrapper = Wrapper(
+            Inner {
+                name: "inner",
+                calls: Cell::new(0),
+            },
+            9,
+        );
+        let obj = Holder { pair: (1, "one") };
+        let pair = (String::from("abc"), 2);
+
+        // tuple index then field
+        assert_eq!(format!("{wrapper.0.name} {wrapper.1}"), "inner 9");
+        // field then tuple index
+        assert_eq!(format!("{obj.pair.1}/{obj.pair.0:02}"), "one/01");
+        // method after a tuple index
+        assert_eq!(format!("{pair.0.len()} {pair.0.to_uppercase()}"), "3 ABC");
+
+        // repeated method on a tuple field runs once
+        assert_eq!(
+            format!("{wrapper.0.method()}+{wrapper.0.method():>2}"),
+            "5+ 5"
+        );
+        assert_eq!(wrapper.0.calls.get(), 1);
+    }
+
+    #[test]
+    fn test_formati_nested_tuple_index() {
+        let tuple = ((1.5, 2), "x");
+        let value = 0.0;
+
+        // `{tuple.0.1}` is field access, not a float literal
+        assert_eq!(format!("{tuple.0.1}"), "2");
+        assert_eq!(format!("{tuple.0.0:.2}|{tuple.1}"), "1.50|x");
+        // a plain float-looking value still goes through `{value}` / `{0}`
+        assert_eq!(format!("{value:.1} {0:.1}", 0.0), "0.0 0.0");
+    }
+
+    #[test]
+    fn test_formati_size_of_and_const_generics() {
+        fn describe<T, const N: usize>(items: [T; N]) -> String {
+            // `{N}` is a plain identifier and is captured by std itself
+            format!(
+                "{N} x {std::mem::size_of::<T>()}B = {core::mem::size_of::<[T; N]>():>3}B ({items.len()})"
+            )
+        }
+
+        assert_eq!(describe([1u32, 2, 3]), "3 x 4B =  12B (3)");
+        assert_eq!(describe([0u8; 5]), "5 x 1B =   5B (5)");
+        assert_eq!(describe::<u64, 0>([]), "0 x 8B =   0B (0)");
+
+        // the turbofish paths are extracted, `{N}` is left to std
+        fn audit<T, const N: usize>() -> formati::FormatAudit {
+            formati::format_audit!("{N}:{std::mem::size_of::<T>()}").1
+        }
+        assert_eq!(audit::<u16, 4>().placeholders(), 1);
+    }
+
+    #[test]
+    fn test_formati_braced_macro_calls() {
+        let x = "3".parse::<u8>().ok();
+
+        assert_eq!(format!("{matches!(x, Some(1..))}"), "true");
+        assert_eq!(format!("[{vec!{1, 2, 3}.len():>3}]"), "[  3]");
+        // braces and quotes inside the macro's own string literals
+        assert_eq!(format!("{concat!{\"a}\", \"{b\"}}|{x:?}"), "a}{b|Some(3)");
+        assert_eq!(
+            format!(
+                "{std::format!{\"<{}>\", x.unwrap_or(0)}.len()} {matches!{x, Some(n) if n > 2}}"
+            ),
+            "3 true"
+        );
+    }
+
+    #[test]
+    fn test_formati_adjacent_placeholders() {
+        use std::cell::Cell;
+
+        struct Point {
+            x: i32,
+            y: i32,
+            z: i32,
+        }
+
+        struct Counter(Cell<u32>);
+
+        impl Counter {
+            fn f(&self) -> u32 {
+                self.0.set(self.0.get() + 1);
+                7
+            }
+        }
+
+        let a = ("left", 2);
+        let obj = Point { x: 1, y: -2, z: 3 };
+        let v = Counter(Cell::new(0));
+
+        assert_eq!(format!("{a.0}{a.1}"), "left2");
+        assert_eq!(format!("{obj.x}{obj.y}{obj.z}"), "1-23");
+        assert_eq!(format!("{obj.x:>2}{obj.y:<3}{obj.z:02}|"), " 1-2 03|");
+        // adjacent to plain placeholders and escaped braces
+        assert_eq!(format!("{{{a.0}}}{}{a.1}", 9), "{left}92");
+
+        assert_eq!(format!("{v.f()}{v.f()}"), "77");
+        assert_eq!(v.0.get(), 1);
+    }
+
+    #[test]
+    fn test_formati_combinator_chains() {
+        use std::cell::Cell;
+
+        let res: Result<String, String> = Ok(String::from("abcd"));
+        let bad: Result<u8, &str> = Err("boom");
+        let opt = Some(3u32);
+        let scale = 10u32;
+        let calls = Cell::new(0);
+
+        assert_eq!(format!("{res.as_ref().map(|x| x.len()).unwrap_or(0)}"), "4");
+        assert_eq!(
+            format!("{res.as_ref().map(|x| x.len()).unwrap_or(0):>4}|"),
+            "   4|"
+        );
+        // closure capturing an outer variable, followed by a numeric spec
+        assert_eq!(
+            format!("{opt.map(|n| n * scale).unwrap_or_default():05}"),
+            "00030"
+        );
+        assert_eq!(
+            format!("{bad.map_err(|e| e.len() + scale as usize).unwrap_err():#x}"),
+            "0xe"
+        );
+        assert_eq!(format!("{opt.filter(|&n| n > 5).is_none()}"), "true");
+
+        // identical chains are evaluated once
+        let counted = |n: u32| {
+            calls.set(calls.get() + 1);
+            n + 1
+        };
+        assert_eq!(
+            format!("{opt.map(counted).unwrap_or(0):.2e} {opt.map(counted).unwrap_or(0)}"),
+            "4.00e0 4"
+        );
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_format_owned() {
+        use formati::format_owned;
+        use std::cell::RefCell;
+        use std::sync::Mutex;
+
+        let queue = RefCell::new(vec![1, 2, 3]);
+
+        // `format!` keeps the `borrow_mut()` guard alive while `borrow()` runs
+        let held = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            format!("{queue.borrow_mut().pop().unwrap()} {queue.borrow().len()}")
+        }));
+        assert!(held.is_err());
+        assert_eq!(queue.borrow().len(), 2);
+
+        assert_eq!(
+            format_owned!("{queue.borrow_mut().pop().unwrap()} left: {queue.borrow().len()}"),
+            "2 left: 1"
+        );
+
+        // locking twice in one template would deadlock under `format!`
+        let totals = Mutex::new((4u32, 6u32));
+        assert_eq!(
+            format_owned!(
+                "{totals.lock().unwrap().0:>3}/{totals.lock().unwrap().1:<3}|{} {n}",
+                'x',
+                n = totals.lock().unwrap().0 + 1
+            ),
+            "  4/6  |x 5"
+        );
+
+        // repeated expressions are still bound once
+        let calls = std::cell::Cell::new(0);
+        let next = || {
+            calls.set(calls.get() + 1);
+            calls.get()
+        };
+        assert_eq!(
+            format_owned!("{next()} {next()} {queue.borrow().len()}"),
+            "1 1 1"
+        );
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_format_owned_borrowed_temporaries() {
+        use formati::format_owned;
+
+        struct User {
+            name: String,
+            tags: Vec<String>,
+        }
+
+        fn greeting() -> String {
+            "  hello ".to_string()
+        }
+
+        let user = User {
+            name: "ada".to_string(),
+            tags: vec!["admin".to_string()],
+        };
+
+        // a `&str` borrowed from a temporary `String` is bound as a `String`
+        assert_eq!(
+            format_owned!("[{greeting().trim()}] {greeting().trim().len():>2}"),
+            "[hello]  5"
+        );
+
+        // places are borrowed, so `user` is still whole afterwards
+        assert_eq!(
+            format_owned!("{user.name}:{user.tags[0]:>6}"),
+            "ada: admin"
+        );
+        assert_eq!(user.name, "ada");
+        assert_eq!(user.tags.len(), 1);
+    }
+
+    #[test]
+    fn test_formati_match_arms_with_guards() {
+        let label = |x: u32| {
+            format!("{match x { 0 | 1 => \"low\", n if n > 9 => \"high\", _ => \"mid\" }}")
+        };
+        assert_eq!(label(1), "low");
+        assert_eq!(label(5), "mid");
+        assert_eq!(label(12), "high");
+
+        let pair = (3, -2);
+        // guards comparing with `<`/`>=`, a block arm, then a spec after the match
+        assert_eq!(
+            format!(
+                "[{match pair { (a, b) if a < b => a, (a, b) if b >= 0 => { let s = a + b; s * 2 } _ => 0 }:>4}]"
+            ),
+            "[   0]"
+        );
+        assert_eq!(
+            format!("{match pair.0 { 1..=2 | 4 => 'a', n if n << 1 > 5 => 'b', _ => 'c' }:?}"),
+            "'b'"
+        );
+        // an unspaced `n<y` guard looks like generics until the `=>`
+        let y = 5;
+        assert_eq!(
+            format!("{match pair.0 { n if n<y => Vec::<u8>::with_capacity(1).len(), _ => 2 }:>3}"),
+            "  0"
+        );
+    }
+
+    #[test]
+    fn test_formati_arrow_and_comparison_tokens() {
+        let (a, b) = (4, 7);
+        let items = [1u8, 2, 3];
+
+        // `->` return type on a closure
+        assert_eq!(format!("{(|x: i32| -> i32 { x * 2 })(a):>3}"), "  8");
+        assert_eq!(
+            format!(
+                "{items.iter().map(|&x| -> Box<dyn Fn() -> u8> { Box::new(move || x) }).map(|f| f()).sum::<u8>():02}"
+            ),
+            "06"
+        );
+        // `>=` / `<=` comparisons before the spec colon
+        assert_eq!(format!("[{a >= b:>6}]"), "[ false]");
+        assert_eq!(format!("[{a <= b:<6}]"), "[true  ]");
+        assert_eq!(format!("[{items.len() as i32 >= a - 1:^7}]"), "[ true  ]");
+        // an unspaced comparison next to a real generic
+        assert_eq!(
+            format!("[{Vec::<u8>::with_capacity(2).capacity()>=a as usize:>6}]"),
+            "[ false]"
+        );
+    }
+
+    #[test]
+    fn test_format_cow() {
+        use formati::format_cow;
+        use std::borrow::Cow;
+
+        let user = ("alice", 42);
+
+        let fixed = format_cow!("no user");
+        assert!(matches!(fixed, Cow::Borrowed("no user")));
+
+        let escaped = format_cow!("{{literal}} braces");
+        assert!(matches!(escaped, Cow::Borrowed("{literal} braces")));
+
+        let label = format_cow!("{user.0}#{user.1:03}");
+        assert!(matches!(&label, Cow::Owned(s) if s == "alice#042"));
+
+        // any argument means formatting, even without an extracted expression
+        let plain = format_cow!("{}", "x");
+        assert!(matches!(&plain, Cow::Owned(s) if s == "x"));
+
+        fn takes_cow(message: Cow<'static, str>) -> usize {
+            message.len()
+        }
+        assert_eq!(takes_cow(format_cow!("static")), 6);
+        assert_eq!(takes_cow(format_cow!("{user.0}")), 5);
+    }
+
+    #[test]
+    fn test_formati_qualified_paths() {
+        fn zeros<const N: usize>() -> [u8; N] {
+            [0; N]
+        }
+
+        let v = vec![1u8, 2];
+
+        assert_eq!(format!("[{<Vec<u8> as Default>::default().len()}]"), "[0]");
+        assert_eq!(
+            format!("[{<Vec<u8> as Default>::default().len():>3}]"),
+            "[  0]"
+        );
+        // nested generics inside the qualified type and trait
+        assert_eq!(format!("[{<u8 as From<bool>>::from(true):02}]"), "[01]");
+        assert_eq!(
+            format!("[{<[u8] as ToOwned>::to_owned(&v).len():<3}]"),
+            "[2  ]"
+        );
+        assert_eq!(
+            format!(
+                "{<Vec<u8> as IntoIterator>::into_iter(v.clone()).map(u32::from).sum::<u32>():#x}"
+            ),
+            "0x3"
+        );
+        // a braced const argument in the turbofish
+        assert_eq!(format!("{zeros::<{ 1 + 2 }>().len():>2}"), " 3");
+
+        let (s, audit) = formati::format_audit!(
+            "{<str as ToString>::to_string(\"ab\"):?} {<str as ToString>::to_string(\"ab\")}"
+        );
+        assert_eq!(s, "\"ab\" ab");
+        assert_eq!(audit.unique_expressions(), 1);
+    }
+
+    #[test]
+    fn test_formati_join_spec() {
+        use std::fmt;
+
+        struct Item {
+            name: &'static str,
+            qty: u32,
+        }
+
+        impl fmt::Display for Item {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}x{}", self.qty, self.name)
+            }
+        }
+
+        let numbers: Vec<i32> = vec![3, -1, 12];
+        let empty: Vec<i32> = Vec::new();
+        let order = [
+            Item {
+                name: "tea",
+                qty: 2,
+            },
+            Item {
+                name: "cake",
+                qty: 1,
+            },
+        ];
+        let width = 5;
+
+        assert_eq!(format!("[{numbers:join(\", \")}]"), "[3, -1, 12]");
+        assert_eq!(format!("[{empty:join(\", \")}]"), "[]");
+        // a per-item spec after the directive, including a captured width
+        assert_eq!(format!("[{numbers:join(\"|\"):>3}]"), "[  3| -1| 12]");
+        assert_eq!(format!("[{numbers:join(\"\"):+}]"), "[+3-1+12]");
+        assert_eq!(
+            format!("[{numbers:join(\",\"):<width$}]"),
+            "[3    ,-1   ,12   ]"
+        );
+        assert_eq!(format!("[{order[..1]:join(\";\")}]"), "[2xtea]");
+        assert_eq!(format!("{order:join(\" + \")}"), "2xtea + 1xcake");
+        // the separator may contain `)` and `:`
+        assert_eq!(format!("{numbers:join(\"):\")}"), "3):-1):12");
+        // on an expression, and alongside the same expression without a join
+        assert_eq!(
+            format!(
+                "{order.iter().map(|i| i.qty).collect::<Vec<_>>():join(\"/\"):02} of {order.len()}"
+            ),
+            "02/01 of 2"
+        );
+    }
+
+    #[test]
+    fn test_formati_discard_shares_evaluation() {
+        use std::cell::Cell;
+
+        struct Obj {
+            calls: Cell<u32>,
+        }
+
+        impl Obj {
+            fn compute(&self) -> u32 {
+                self.calls.set(self.calls.get() + 1);
+                42
+            }
+        }
+
+        let obj = Obj {
+            calls: Cell::new(0),
+        };
+
+        // plain, spec'd and discarded forms of one expression share one evaluation
+        let s = format!("[{obj.compute()}][{obj.compute():>8}][{_= obj.compute()}]");
+        assert_eq!(s, "[42][      42][]");
+        assert_eq!(obj.calls.get(), 1);
+
+        // spacing inside the placeholder doesn't split the namespace either
+        let s = format!("{_ = obj.compute()}{ obj.compute() }|{obj .compute()}");
+        assert_eq!(s, "42|42");
+        assert_eq!(obj.calls.get(), 2);
+
+        // only discarded: still evaluated once, writes nothing
+        let s = format!("<{_ = obj.compute()}{_=obj.compute()}>");
+        assert_eq!(s, "<>");
+        assert_eq!(obj.calls.get(), 3);
+
+        let (_, audit) = formati::format_audit!("{obj.compute()}{_ = obj.compute()}");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (2, 1));
+    }
+
+    #[test]
+    fn test_formati_raw_identifiers() {
+        #[allow(non_camel_case_types)]
+        struct r#match {
+            r#type: &'static str,
+        }
+
+        struct Config {
+            r#async: bool,
+            r#match: r#match,
+        }
+
+        impl Config {
+            fn r#yield(&self) -> u8 {
+                7
+            }
+        }
+
+        let cfg = Config {
+            r#async: true,
+            r#match: r#match { r#type: "glob" },
+        };
+        let r#type = "kind";
+
+        assert_eq!(format!("{cfg.r#async}"), "true");
+        // the `#` of a raw ident isn't mistaken for the alternate flag, and vice versa
+        assert_eq!(format!("{cfg.r#match.r#type:#?}"), "\"glob\"");
+        assert_eq!(format!("{cfg.r#match.r#type:>6}"), "  glob");
+        assert_eq!(format!("{cfg.r#yield()}|{cfg.r#yield() + 1:03}"), "7|008");
+        // a bare raw ident can't be captured by `format_args!`, so it's bound too
+        assert_eq!(format!("{r#type}={cfg.r#match.r#type}"), "kind=glob");
+
+        let (_, audit) = formati::format_audit!("{cfg.r#yield()}{cfg.r#yield()}{r#type}");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (3, 2));
+    }
+
+    #[test]
+    fn test_formati_index_with_computed_key() {
+        use std::cell::Cell;
+        use std::collections::HashMap;
+
+        let calls = Cell::new(0);
+        let compute_key = |id: u32| {
+            calls.set(calls.get() + 1);
+            format!("user:{}", id)
+        };
+
+        let mut cache = HashMap::new();
+        cache.insert("user:1".to_string(), "ada".to_string());
+        cache.insert("user:2".to_string(), "grace".to_string());
+        let id = 2;
+
+        // nested parens inside the brackets, with a spec on one side
+        let s = format!(
+            "{cache[&compute_key(id)]}/{cache[&compute_key(id)]:>7}/{cache[&compute_key(id - 1)]}"
+        );
+        assert_eq!(s, "grace/  grace/ada");
+        assert_eq!(calls.get(), 2);
+
+        // the deduped binding borrows the map entry, so its methods work too
+        let s = format!("{cache[&compute_key(id)].len()}:{cache[&compute_key(id)].to_uppercase()}");
+        assert_eq!(s, "5:GRACE");
+        assert_eq!(calls.get(), 4);
+
+        // any `Index` impl with a `&`-prefixed key
+        struct Table(Vec<&'static str>);
+        impl std::ops::Index<&(usize, usize)> for Table {
+            type Output = &'static str;
+            fn index(&self, &(row, col): &(usize, usize)) -> &Self::Output {
+                &self.0[row * 2 + col]
+            }
+        }
+        let table = Table(vec!["a", "b", "c", "d"]);
+        let row = 1;
+        assert_eq!(
+            format!("{table[&(row, 0)]}{table[&(row - 1, 1)]}{table[&(row, 0)]}"),
+            "cbc"
+        );
+    }
+
+    #[test]
+    fn test_formatdoc_dedent_with_padding() {
+        use formati::formatdoc;
+
+        struct Row {
+            name: &'static str,
+            hits: u32,
+        }
+
+        let rows = [
+            Row {
+                name: "index",
+                hits: 7,
+            },
+            Row {
+                name: "search-results",
+                hits: 1024,
+            },
+        ];
+
+        // padding follows the rendered widths; the literal loses its indentation
+        let table = formatdoc!(
+            "
+            {rows[0].name:<10}|{rows[0].hits:>6}
+            {rows[1].name:<10}|{rows[1].hits:>6}
+              total: {rows[0].hits + rows[1].hits:>5}
+            "
+        );
+        assert_eq!(
+            table,
+            "index     |     7\nsearch-results|  1024\n  total:  1031\n"
+        );
+
+        // values that span lines or start with whitespace are left alone
+        let block = "  a\n    b";
+        let s = formatdoc!(
+            "
+                [{block}]
+                [{block.len():^7}]"
+        );
+        assert_eq!(s, "[  a\n    b]\n[   9   ]");
+
+        // blank lines don't count towards the shared indentation
+        let s = formatdoc!(
+            "
+            {rows[0].name}
+
+                {rows[0].hits:03}"
+        );
+        assert_eq!(s, "index\n\n    007");
+    }
+
+    #[test]
+    fn test_formati_duration_units() {
+        use std::time::Duration;
+
+        struct Timing {
+            elapsed: Duration,
+        }
+
+        let t = Timing {
+            elapsed: Duration::from_nanos(1_234_567_891),
+        };
+
+        assert_eq!(format!("{t.elapsed:s}"), "1.234567891");
+        assert_eq!(format!("{t.elapsed:ms}ms"), "1234.567891ms");
+        assert_eq!(format!("{t.elapsed:us}"), "1234567.891");
+        assert_eq!(format!("{t.elapsed:ns}"), "1234567891");
+
+        // rounded to the given precision
+        assert_eq!(format!("{t.elapsed:s.2}"), "1.23");
+        assert_eq!(format!("{t.elapsed:ms.0}|{t.elapsed:ms.1}"), "1235|1234.6");
+        assert_eq!(format!("{t.elapsed:us.4}"), "1234567.8910");
+
+        // a local works too, and std's own format traits are untouched
+        let d = Duration::from_micros(1500);
+        assert_eq!(format!("{d:ms} {d:?} {d.as_micros():x}"), "1.5 1.5ms 5dc");
+
+        // each unit is evaluated once per expression
+        let (_, audit) = formati::format_audit!("{t.elapsed:ms}{t.elapsed:ms}{t.elapsed:s}");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (3, 2));
+    }
+
+    #[test]
+    fn test_formati_parenthesized_ref_and_deref() {
+        use std::rc::Rc;
+
+        struct Counter {
+            value: u32,
+        }
+
+        let obj = vec![1, 2, 3];
+        let rc = Rc::new(Counter { value: 9 });
+        let boxed = Box::new(Counter { value: 4 });
+        let mut v: Vec<u8> = Vec::new();
+
+        // a leading `(` still extracts, whatever follows it
+        assert_eq!(
+            format!("{(&obj).len()}|{(*rc).value}|{(*boxed).value:>3}"),
+            "3|9|  4"
+        );
+        assert_eq!(format!("{(obj.len())}{(rc.value)}"), "39");
+        assert_eq!(format!("{(&obj)[1]}{(&*obj).first().unwrap()}"), "21");
+
+        // deduplicated: `v` is pushed to once, through a `&mut` in the head
+        let s = format!("{(&mut v).push(7):?}{(&mut v).push(7):?}");
+        assert_eq!(s, "()()");
+        assert_eq!(v, [7]);
+
+        // shared borrows of `obj` in the bindings don't outlive the call
+        let s = format!("{(&obj).iter().sum::<i32>()}/{(&obj).len()}");
+        drop(obj);
+        assert_eq!(s, "6/3");
+    }
+
+    #[test]
+    fn test_formati_redundant_parens() {
+        let x = 5;
+        let name = "ada";
+
+        // redundant parens are extracted like any other expression
+        assert_eq!(
+            format!("{(x)}|{((x))}|{(x):>3}|{(name):?}"),
+            "5|5|  5|\"ada\""
+        );
+        assert_eq!(format!("{(42)}|{(-1)}|{(2.5):.2}|{('c')}"), "42|-1|2.50|c");
+
+        // outer parens don't split the dedup namespace
+        let (_, audit) = formati::format_audit!("{(x)}{((x))}{( x )}{(x + 1)}{x + 1}");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (5, 2));
+
+        // being an expression, `(x)` is the local, never a named argument `x`
+        assert_eq!(format!("{x}/{(x)}", x = 7), "7/5");
+    }
+
+    #[test]
+    fn test_formati_in_generic_functions() {
+        use std::fmt::{Debug, Display};
+
+        fn describe<T: Display>(x: &T) -> String {
+            format!("{x}|{x.to_string()}|{x.to_string().len():>2}|{x.to_string()}")
+        }
+
+        struct Pair<A, B> {
+            a: A,
+            b: B,
+        }
+
+        fn pair<A: Debug, B: Display + Copy>(p: &Pair<A, B>) -> String {
+            format!("{p.a:?}/{p.b}/{[p.b, p.b].len()}")
+        }
+
+        fn sized<T, const N: usize>(items: [T; N]) -> String
+        where
+            T: Display,
+        {
+            format!("{N}:{items.len()}:{items[0]}")
+        }
+
+        assert_eq!(describe(&42), "42|42| 2|42");
+        assert_eq!(describe(&"hi"), "hi|hi| 2|hi");
+        assert_eq!(pair(&Pair { a: vec![1], b: 'z' }), "[1]/z/2");
+        assert_eq!(sized(["x", "y", "z"]), "3:3:x");
+    }
+
+    #[test]
+    fn test_formati_error_chain() {
+        use std::error::Error;
+        use std::fmt;
+
+        #[derive(Debug)]
+        struct ConfigError {
+            path: &'static str,
+            source: std::num::ParseIntError,
+        }
+
+        impl fmt::Display for ConfigError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "cannot load {}", self.path)
+            }
+        }
+
+        impl Error for ConfigError {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.source)
+            }
+        }
+
+        struct Outcome {
+            err: ConfigError,
+        }
+
+        let outcome = Outcome {
+            err: ConfigError {
+                path: "app.toml",
+                source: "x1".parse::<u8>().unwrap_err(),
+            },
+        };
+
+        assert_eq!(
+            format!("{outcome.err:chain}"),
+            "cannot load app.toml: invalid digit found in string"
+        );
+        assert_eq!(format!("{outcome.err}"), "cannot load app.toml");
+
+        // an error without a source is just its message
+        let err = "".parse::<i32>().unwrap_err();
+        assert_eq!(
+            format!("[{err:chain}]"),
+            "[cannot parse integer from empty string]"
+        );
+
+        // trait objects work through their box
+        let boxed: Box<dyn Error> = Box::new(outcome.err);
+        assert_eq!(
+            format!("{boxed:chain}"),
+            "cannot load app.toml: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_formati_methods_on_numeric_literals() {
+        assert_eq!(format!("{2_i32.pow(10)}"), "1024");
+        assert_eq!(format!("{3.0_f64.sqrt():.4}"), "1.7321");
+        assert_eq!(
+            format!("{1u8.saturating_add(2):>3}|{0x10u16.count_ones()}"),
+            "  3|1"
+        );
+        assert_eq!(
+            format!("{2.5f32.floor()}|{(7.5_f64).ceil():05.1}"),
+            "2|008.0"
+        );
+
+        // unary minus binds looser than the method call, as in Rust
+        assert_eq!(format!("{-4i32.abs()}|{(-4i32).abs()}"), "-4|4");
+
+        // a bare integer is still a positional index
+        assert_eq!(format!("{1}|{0}", "a", "b"), "b|a");
+        let (_, audit) = formati::format_audit!("{2_i32.pow(10)}{2_i32.pow(10)}");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (2, 1));
+    }
+
+    #[test]
+    fn test_formati_function_pointer_and_closure_return_types() {
+        fn double(x: i32) -> i32 {
+            x * 2
+        }
+
+        let f = double;
+        let table: [fn(i32) -> i32; 2] = [double, |x| x + 1];
+
+        assert_eq!(format!("{(f as fn(i32) -> i32)(3)}"), "6");
+        assert_eq!(format!("[{(f as fn(i32) -> i32)(3):>4}]"), "[   6]");
+        assert_eq!(
+            format!("{table[1](41)}|{table.map(|g| g(1))[0]:03}"),
+            "42|002"
+        );
+
+        // closures with an explicit return type, with and without a block body
+        assert_eq!(format!("{(|x: u8| -> u16 { x as u16 * 300 })(2):x}"), "258");
+        assert_eq!(
+            format!("{[1, 2].map(|x| -> Vec<u8> { vec![x; 2] }):?}"),
+            "[[1, 1], [2, 2]]"
+        );
+        assert_eq!(
+            format!("{Some(3).map(|n: u32| -> Option<u32> { n.checked_sub(5) }):?}"),
+            "Some(None)"
+        );
+    }
+
+    #[test]
+    fn test_format_case_and_trim() {
+        use formati::{format_lower, format_trim, format_upper};
+        use std::cell::Cell;
+
+        struct Rec {
+            id: u32,
+            kind: &'static str,
+            reads: Cell<u32>,
+        }
+
+        impl Rec {
+            fn tag(&self) -> &'static str {
+                self.reads.set(self.reads.get() + 1);
+                " Beta "
+            }
+        }
+
+        let rec = Rec {
+            id: 7,
+            kind: "Draft",
+            reads: Cell::new(0),
+        };
+
+        // literal text and values are transformed alike
+        assert_eq!(format_upper!("id-{rec.id}: {rec.kind}"), "ID-7: DRAFT");
+        assert_eq!(format_lower!("ID-{rec.id}: {rec.kind:>7}"), "id-7:   draft");
+
+        // only the ends of the whole result are trimmed, not each value
+        assert_eq!(format_trim!("{rec.tag()}|{rec.tag()}"), "Beta | Beta");
+        assert_eq!(rec.reads.get(), 1);
+        assert_eq!(format_trim!("  {}  ", rec.id), "7");
+        assert_eq!(format_upper!("{:?}", rec.kind), "\"DRAFT\"");
+    }
+
+    #[test]
+    fn test_formati_atomic_load_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Counted {
+            value: AtomicUsize,
+            loads: AtomicUsize,
+        }
+
+        impl Counted {
+            fn load(&self, order: Ordering) -> usize {
+                self.loads.fetch_add(1, Ordering::Relaxed);
+                self.value.load(order)
+            }
+        }
+
+        struct Stats {
+            counter: Counted,
+        }
+
+        let stats = Stats {
+            counter: Counted {
+                value: AtomicUsize::new(12),
+                loads: AtomicUsize::new(0),
+            },
+        };
+
+        // the `::` of the path argument is not taken for a spec separator
+        let s = format!(
+            "{stats.counter.load(Ordering::Relaxed)} / {stats.counter.load(Ordering::Relaxed):>4}"
+        );
+        assert_eq!(s, "12 /   12");
+        assert_eq!(stats.counter.loads.load(Ordering::Relaxed), 1);
+
+        // a different ordering is a different expression
+        let s = format!(
+            "{stats.counter.load(Ordering::SeqCst):x}{stats.counter.load(std::sync::atomic::Ordering::Acquire):?}"
+        );
+        assert_eq!(s, "c12");
+        assert_eq!(stats.counter.loads.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_formati_string_arguments_with_spec_characters() {
+        let url = String::from("http://example.com:8080/a");
+
+        assert_eq!(
+            format!("{url.replace(\"http://\", \"https://\")}"),
+            "https://example.com:8080/a"
+        );
+        assert_eq!(
+            format!("[{url.replace(\"http://\", \"\"):>24}]"),
+            "[      example.com:8080/a]"
+        );
+        assert_eq!(format!("{url.split(':').count():04}"), "0003");
+        assert_eq!(
+            format!("{url.len():04}|{url.contains(\"::\")}"),
+            "0025|false"
+        );
+
+        // braces and `%` inside string arguments neither close the placeholder
+        // nor start a spec
+        let tpl = "{name}: 100%";
+        assert_eq!(
+            format!("{tpl.replace(\"{name}\", \"cpu\"):?}"),
+            "\"cpu: 100%\""
+        );
+        assert_eq!(
+            format!("{tpl.trim_start_matches('{').split('}').next().unwrap():>6}"),
+            "  name"
+        );
+        assert_eq!(format!("{[\"a:b\", \"}{\"].concat()}|{'%'}"), "a:b}{|%");
+    }
+
+    #[test]
+    fn test_formati_associated_type_method_chains() {
+        use std::cell::Cell;
+
+        trait Source {
+            type Item: std::fmt::Display;
+
+            fn items(&self) -> &[Self::Item];
+
+            // default method formatting a chain over an associated type
+            fn describe(&self) -> String {
+                format!(
+                    "first={self.items().iter().next().unwrap()} last={self.items().last().unwrap()} n={self.items().len()}"
+                )
+            }
+        }
+
+        struct Words {
+            words: Vec<String>,
+            lookups: Cell<u32>,
+        }
+
+        impl Words {
+            // returns a reference tied to `self`
+            fn longest(&self) -> &str {
+                self.lookups.set(self.lookups.get() + 1);
+                self.words.iter().max_by_key(|w| w.len()).unwrap()
+            }
+
+            fn report(&self) -> String {
+                format!("{self.longest()} ({self.longest().len()}) / {self.longest()}")
+            }
+        }
+
+        impl Source for Words {
+            type Item = String;
+
+            fn items(&self) -> &[String] {
+                &self.words
+            }
+        }
+
+        let words = Words {
+            words: vec!["ab".into(), "abcd".into(), "abc".into()],
+            lookups: Cell::new(0),
+        };
+
+        assert_eq!(words.describe(), "first=ab last=abc n=3");
+        assert_eq!(words.report(), "abcd (4) / abcd");
+        // `{self.longest()}` is bound once and reused; `.len()` is its own
+        // expression
+        assert_eq!(words.lookups.get(), 2);
+        // `self` is only borrowed; the value is still usable afterwards
+        assert_eq!(words.items().len(), 3);
+    }
+
+    #[test]
+    fn test_formati_labeled_block() {
+        let x = 21;
+        let words = ["ab", "c:d", "ef"];
+
+        // the label's `'` doesn't open a char literal, so the block's braces
+        // balance, and its `:` isn't taken for a spec
+        assert_eq!(
+            format!("{'calc: { if x > 10 { break 'calc x * 2; } x }}"),
+            "42"
+        );
+        assert_eq!(
+            format!("[{'calc: { if x > 10 { break 'calc x * 2; } x }:>4}]"),
+            "[  42]"
+        );
+
+        // early exit from the block, with char literals (including `'}'` and
+        // `':'`) next to the label
+        assert_eq!(
+            format!(
+                "{'find: { for w in words { if w.contains(':') { break 'find w.replace(':', \"}\"); } } String::from('}') }:?}"
+            ),
+            "\"c}d\""
+        );
+
+        // a labeled loop, and a block yielding a char literal
+        let s = format!(
+            "{'outer: loop { for n in x.. { if n % 5 == 0 { break 'outer n; } } }}|{'b: { if x < 0 { break 'b '-'; } ':' }}"
+        );
+        assert_eq!(s, "25|:");
+    }
+
+    #[test]
+    fn test_formati_statement_blocks() {
+        let a = 3;
+        let b = 4;
+        let words = ["x}y", "z"];
+
+        // a space keeps the block's `{` from reading as an escaped `{{`
+        assert_eq!(format!("{ { let t = a + b; t * 2 } }"), "14");
+        assert_eq!(format!("[{ { let t = a + b; t * 2 }:>4}]"), "[  14]");
+
+        // nested blocks, and a string literal holding `}` inside the block
+        let s = format!(
+            "{ { let joined = words.join(\"}\"); let n = { let m = joined.len(); m + 1 }; format!(\"{joined}/{n}\") }:?}"
+        );
+        assert_eq!(s, "\"x}y}z/6\"");
+
+        // the `;`s inside the block aren't an explicit spec separator, but one
+        // after it still is
+        assert_eq!(format!("{ { let v = a * b; v } ; 04}"), "0012");
+    }
+
+    #[test]
+    fn test_format_sep() {
+        use formati::format_sep;
+
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+        let a = Point { x: 1, y: -2 };
+        let b = (Point { x: 3, y: 4 }, "origin");
+
+        assert_eq!(
+            format_sep!(" | ", a.x, a.y, b.0.x, b.1.len(), b.1),
+            "1 | -2 | 3 | 6 | origin"
+        );
+        assert_eq!(format_sep!(" | ", b.0.y), "4");
+        assert_eq!(format_sep!(" | "), "");
+        assert_eq!(format_sep!(", ",), "");
+
+        // braces in the separator are literal text
+        assert_eq!(format_sep!("}{", a.x, b.0.x,), "1}{3");
+    }
+
+    #[test]
+    fn test_formati_reference_heads_with_debug_spec() {
+        use std::collections::BTreeMap;
+
+        struct Inventory {
+            items: Vec<&'static str>,
+        }
+
+        impl Inventory {
+            fn dump(&self) -> String {
+                format!("{&self.items:#?}")
+            }
+        }
+
+        let map = BTreeMap::from([("a", 1), ("b", 2)]);
+        assert_eq!(format!("{&map:?}"), "{\"a\": 1, \"b\": 2}");
+        assert_eq!(format!("{&map:?}/{&map.len():?}"), "{\"a\": 1, \"b\": 2}/2");
+
+        let mut buf = vec![1, 2];
+        assert_eq!(format!("{&mut buf:?}"), "[1, 2]");
+        buf.push(3);
+
+        let inv = Inventory {
+            items: vec!["bolt", "nut"],
+        };
+        assert_eq!(inv.dump(), "[\n    \"bolt\",\n    \"nut\",\n]");
+
+        // nothing was moved out: every value is still usable
+        assert_eq!(map.len(), 2);
+        assert_eq!(buf, [1, 2, 3]);
+        assert_eq!(inv.items.len(), 2);
+    }
+
+    #[test]
+    fn test_formati_2d_indexing() {
+        use std::cell::Cell;
+
+        struct Grid {
+            cells: Vec<Vec<u32>>,
+            reads: Cell<u32>,
+        }
+
+        impl Grid {
+            fn rows(&self) -> &Vec<Vec<u32>> {
+                self.reads.set(self.reads.get() + 1);
+                &self.cells
+            }
+        }
+
+        let grid = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let (r, c) = (1, 2);
+        let (i, j) = (1, 1);
+
+        assert_eq!(format!("{grid[r][c]}"), "6");
+        assert_eq!(format!("{grid[i+1][j-1]}|{grid[i + 1][j - 1]:>3}"), "7|  7");
+        assert_eq!(format!("{grid[r][c].to_string().repeat(2)}"), "66");
+        assert_eq!(format!("{grid[0][grid[r][0] as usize - 4]:?}"), "1");
+        assert_eq!(format!("{grid[r][c]:02}:{&grid[r][..c]:?}"), "06:[4, 5]");
+
+        // the whole chained index is one expression, deduplicated across
+        // placeholders regardless of spacing
+        let g = Grid {
+            cells: grid.clone(),
+            reads: Cell::new(0),
+        };
+        assert_eq!(
+            format!("{g.rows()[r][c]} {g.rows()[r] [c]:x} {g.rows()[c][r]}"),
+            "6 6 8"
+        );
+        assert_eq!(g.reads.get(), 2);
+    }
+
+    #[test]
+    fn test_formati_iterator_closure_turbofish_chain() {
+        use std::cell::Cell;
+
+        let parses = Cell::new(0);
+        let items = ["3", "x", "4", "35"];
+        let parse = |x: &str| {
+            parses.set(parses.get() + 1);
+            x.parse::<i32>()
+        };
+
+        let s = format!(
+            "{items.iter().map(|x| x.parse::<i32>().unwrap_or(0)).sum::<i32>()}|{items.iter().map(|x| x.parse::<i32>().unwrap_or(0)).sum::<i32>():04}"
+        );
+        assert_eq!(s, "42|0042");
+
+        // `?` inside a closure returning `Option`, repeated with different
+        // spacing: one extraction, evaluated once
+        let s = format!(
+            "{items.iter().map(|x| parse(x).ok()).collect::<Option<Vec<i32>>>().map_or(-1, |v| v.len() as i32):04} {items.iter().filter_map(|x| -> Option<i32> { Some(parse(x).ok()? * 2) }).sum::<i32>():>5} {items.iter().map( |x| parse(x).ok() ).collect::<Option<Vec<i32>>>().map_or(-1, |v| v.len() as i32)}"
+        );
+        assert_eq!(s, "-001    84 -1");
+        assert_eq!(parses.get(), 2 + 4);
+    }
+
+    #[test]
+    fn test_formati_pipe_closure() {
+        use std::cell::Cell;
+
+        let renders = Cell::new(0);
+        let currency = |cents: &i64| {
+            renders.set(renders.get() + 1);
+            format!("${}.{:02}", cents / 100, cents % 100)
+        };
+        let order = (1999_i64, 250_i64);
+
+        let s = format!(
+            "total {order.0 + order.1 | |c| currency(c)}, item {order.0 | |c| currency(c):>8}, again {order.0+order.1 | |c| currency(c)}"
+        );
+        assert_eq!(s, "total $22.49, item   $19.99, again $22.49");
+        // once per unique placeholder, however often it repeats
+        assert_eq!(renders.get(), 2);
+
+        // the closure borrows the value, which needn't be `Copy`, and may
+        // return anything `Display`
+        let names = vec![String::from("ada"), String::from("grace")];
+        assert_eq!(
+            format!("{names | |n| n.join(\" & \")} ({names | |n| n.len()})"),
+            "ada & grace (2)"
+        );
+        assert_eq!(names.len(), 2);
+
+        // a typed closure parameter needs the explicit `;` spec separator
+        assert_eq!(format!("[{order.1 | |c: &i64| c / 100 ; >3}]"), "[  2]");
+
+        // a plain `|` is still bitwise or
+        let flags = (0b0101, 0b0010);
+        assert_eq!(format!("{flags.0 | flags.1:04b}"), "0111");
+    }
+
+    #[test]
+    fn test_formati_nonzero_and_newtypes() {
+        use std::cell::Cell;
+        use std::num::NonZeroU32;
+        use std::ops::Deref;
+
+        struct Meters(u32);
+
+        struct Tracked {
+            value: u32,
+            derefs: Cell<u32>,
+        }
+
+        impl Deref for Tracked {
+            type Target = u32;
+
+            fn deref(&self) -> &u32 {
+                self.derefs.set(self.derefs.get() + 1);
+                &self.value
+            }
+        }
+
+        let id = NonZeroU32::new(42).unwrap();
+        assert_eq!(format!("{id.get()}|{id.get():#x}|{id}"), "42|0x2a|42");
+
+        let nt = Meters(7);
+        assert_eq!(format!("{nt.0}m {nt.0:03}m {nt.0 * 2}m"), "7m 007m 14m");
+
+        let newtype = Tracked {
+            value: 9,
+            derefs: Cell::new(0),
+        };
+        assert_eq!(
+            format!("{*newtype} {*newtype:04} {* newtype:>3}"),
+            "9 0009   9"
+        );
+        // one extraction for all three placeholders
+        assert_eq!(newtype.derefs.get(), 1);
+    }
+
+    #[test]
+    fn test_formati_char_numeric_specs() {
+        struct Glyph {
+            ch: char,
+        }
+        let g = Glyph { ch: 'é' };
+
+        // `char` itself has no `LowerHex`, so its code point goes through a
+        // cast (or `u32::from`) before the numeric spec
+        assert_eq!(format!("{g.ch as u32:#x}"), "0xe9");
+        assert_eq!(format!("{u32::from(g.ch):#X}"), "0xE9");
+        assert_eq!(
+            format!("{(g.ch as u32):#06x}|{(g.ch as u32):06}"),
+            "0x00e9|000233"
+        );
+        assert_eq!(format!("[{g.ch:>4}][{g.ch:-<3}]"), "[   é][é--]");
+
+        // char literals in the head, including `:` and `}`, stay part of it
+        assert_eq!(format!("{'A' as u32:#04x}"), "0x41");
+        assert_eq!(format!("{(':' as u32):#x}/{g.ch.max('}'):>2}"), "0x3a/ é");
+        assert_eq!(
+            format!("{g.ch == ':':?}|{['{', g.ch][0] as u8:08b}"),
+            "false|01111011"
+        );
+    }
+
+    #[test]
+    fn test_formati_weak_upgrade_once() {
+        use std::cell::Cell;
+        use std::rc::{Rc, Weak};
+
+        struct Node {
+            value: u32,
+            label: &'static str,
+        }
+
+        struct Handle {
+            node: Weak<Node>,
+            upgrades: Cell<u32>,
+        }
+
+        impl Handle {
+            fn upgrade(&self) -> Option<Rc<Node>> {
+                self.upgrades.set(self.upgrades.get() + 1);
+                self.node.upgrade()
+            }
+        }
+
+        let node = Rc::new(Node {
+            value: 7,
+            label: "leaf",
+        });
+        let handle = Handle {
+            node: Rc::downgrade(&node),
+            upgrades: Cell::new(0),
+        };
+        let weak = Rc::downgrade(&node);
+
+        assert_eq!(
+            format!(
+                "{handle.upgrade().unwrap().value} / {handle.upgrade().unwrap().value:03} / {handle.upgrade() .unwrap() .value:x}"
+            ),
+            "7 / 007 / 7"
+        );
+        assert_eq!(handle.upgrades.get(), 1);
+
+        // a different field is a different expression, with its own upgrade
+        assert_eq!(
+            format!("{handle.upgrade().unwrap().label}={handle.upgrade().unwrap().value}"),
+            "leaf=7"
+        );
+        assert_eq!(handle.upgrades.get(), 3);
+
+        // plain `Weak::upgrade` too; the temporary `Rc`s are gone afterwards
+        assert_eq!(
+            format!("{weak.upgrade().unwrap().value}{weak.upgrade().unwrap().value}"),
+            "77"
+        );
+        assert_eq!(Rc::strong_count(&node), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_format_trunc() {
+        use formati::format_trunc;
+
+        let line = ("GET", "/api/users/42");
+
+        // 17 chars: fits exactly, or is cut with the ellipsis counted in
+        assert_eq!(
+            format_trunc!(17, "...", "{line.0} {line.1}"),
+            "GET /api/users/42"
+        );
+        assert_eq!(
+            format_trunc!(16, "...", "{line.0} {line.1}"),
+            "GET /api/user..."
+        );
+        assert_eq!(format_trunc!(10, "…", "{line.0} {line.1}"), "GET /api/…");
+
+        // lengths are chars, and multibyte ones are never split
+        let name = "Zoë Ågren";
+        assert_eq!(format_trunc!(9, "…", "{name}"), "Zoë Ågren");
+        assert_eq!(format_trunc!(8, "…", "{name}"), "Zoë Ågr…");
+        assert_eq!(format_trunc!(5, "…", "{name}"), "Zoë …");
+        assert_eq!(format_trunc!(3, "", "{name}:{name.len()}"), "Zoë");
+        assert_eq!(format_trunc!(4, "→", "日本語のテキスト"), "日本語→");
+
+        // an ellipsis longer than the limit is cut too
+        assert_eq!(format_trunc!(2, "...", "{line.1}"), "..");
+        assert_eq!(format_trunc!(0, "…", "{line.0}"), "");
+    }
+
+    #[test]
+    fn test_long_template() {
+        use std::cell::Cell;
+
+        struct Row {
+            cells: Vec<u32>,
+            calls: Cell<u32>,
+        }
+
+        impl Row {
+            fn get<T: From<u32>>(&self, idx: usize) -> T {
+                self.calls.set(self.calls.get() + 1);
+                T::from(self.cells[idx])
+            }
+        }
+
+        let row = Row {
+            cells: (0..8).collect(),
+            calls: Cell::new(0),
+        };
+        let limit = 4;
+
+        // a long head mixing spaced generics and comparisons
+        let s = format!(
+            "{row.get::<u64> (1) + row.get::<u64> (2) * (row.cells.len() as u64) + (row.cells[3] < limit) as u64 + row.get::<u64> (4) + (limit > row.cells[5]) as u64 + Vec::<u8> ::new().len() as u64 + row.get::<u64> (6) + (row.cells[7] <= limit) as u64:>6}"
+        );
+        assert_eq!(s, "    28");
+        assert_eq!(row.calls.get(), 4);
+
+        // many placeholders, with discards both before and after the values
+        // they share an expression with
+        row.calls.set(0);
+        let s = format!(
+            "{_ = row.get::<u32>(0)}{row.cells[0]}{row.cells[1]:02}{_ = row.get::<u32>(1)}{row.cells[2]:x}{row.get::<u32>(1)}|{row.cells[3]}{_ = row.get::<u64>(2)}{row.cells[4]:>2}{row.cells[5]:<2}{_ = row.get::<u32>(3)}{row.cells[6]}{row.cells[7]:?}{row.get::<u32>(3)}|{row.cells[0] < limit}{row.cells[7] > limit}{_ = row.cells.len()}"
+        );
+        assert_eq!(s, "00121|3 45 673|truetrue");
+        assert_eq!(row.calls.get(), 4);
+    }
+
+    #[test]
+    fn test_escaped_char_literals_in_heads() {
+        let s = "a:b}c";
+
+        // the escapes stand for `:` and `}`, and neither ends the head
+        assert_eq!(format!(r"{s.split('\x3a').count():02}"), "02");
+        assert_eq!(format!(r"{s.split('\u{7d}').count():02}"), "02");
+        assert_eq!(
+            format!(r#"{s.replace('\u{3a}', "=").replace('\x7d', "]"):>6}"#),
+            " a=b]c"
+        );
+        assert_eq!(
+            format!(r"{s.contains('\''):?}|{s.contains('\\')}"),
+            "false|false"
+        );
+    }
+
+    struct Limits {
+        name: &'static str,
+        timeout: (u64, &'static str),
+    }
+
+    static LIMITS: Limits = Limits {
+        name: "default",
+        timeout: (30, "s"),
+    };
+
+    const RETRIES: [u8; 3] = [1, 2, 4];
+
+    mod limits {
+        use formati::format;
+
+        pub const MAX: u64 = 120;
+
+        pub fn describe() -> String {
+            format!("{super::LIMITS.name} <= {self::MAX}s, {super::RETRIES.len()} retries")
+        }
+    }
+
+    #[test]
+    fn test_formati_item_paths() {
+        assert_eq!(
+            format!(
+                "{crate::test_formati::LIMITS.name}: {crate::test_formati::LIMITS.timeout.0:>3}{LIMITS.timeout.1}"
+            ),
+            "default:  30s"
+        );
+        assert_eq!(
+            format!("{self::LIMITS.timeout:?} {self::RETRIES[2]:02} {limits::MAX:#x}"),
+            "(30, \"s\") 04 0x78"
+        );
+        assert_eq!(limits::describe(), "default <= 120s, 3 retries");
+
+        // a leading `::` is an absolute path like any other
+        assert_eq!(
+            format!(
+                "{::std::env::consts::OS.is_empty()} {::core::primitive::u8::MAX:>4} {::core::f64::consts::E:.2}"
+            ),
+            "false  255 2.72"
+        );
+
+        // one spelling of a path is one expression, however it is spaced
+        let (s, audit) = formati::format_audit!(
+            "{self::LIMITS.timeout.0}/{self :: LIMITS.timeout.0:03}/{::core::primitive::u8::MAX}{:: core :: primitive :: u8 :: MAX:x}"
+        );
+        assert_eq!(s, "30/030/255ff");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (4, 2));
+    }
 }
@@ -0,0 +1,77 @@
+#![cfg(feature = "alloc")]
+mod test_formattable {
+    use formati::{Formattable, format};
+    use std::cell::Cell;
+
+    /// No `Display`: the only string form is the canonical one
+    struct Version {
+        major: u16,
+        minor: u16,
+        pre: Option<&'static str>,
+        renders: Cell<u32>,
+    }
+
+    impl Formattable for Version {
+        fn formati(&self) -> String {
+            self.renders.set(self.renders.get() + 1);
+            match self.pre {
+                Some(pre) => format!("v{self.major}.{self.minor}-{pre}"),
+                None => format!("v{self.major}.{self.minor}"),
+            }
+        }
+    }
+
+    struct Package {
+        name: &'static str,
+        version: Version,
+    }
+
+    impl Package {
+        fn version(&self) -> &Version {
+            &self.version
+        }
+    }
+
+    fn package(pre: Option<&'static str>) -> Package {
+        Package {
+            name: "formati",
+            version: Version {
+                major: 0,
+                minor: 1,
+                pre,
+                renders: Cell::new(0),
+            },
+        }
+    }
+
+    #[test]
+    fn test_formattable_directive() {
+        let pkg = package(None);
+        assert_eq!(format!("{pkg.name} {pkg.version:*}"), "formati v0.1");
+
+        // through a reference, and next to other specs
+        let beta = package(Some("beta"));
+        assert_eq!(
+            format!("{beta.version():*} ({beta.version.major:02}.{beta.version.minor:02})"),
+            "v0.1-beta (00.01)"
+        );
+
+        let version = &pkg.version;
+        assert_eq!(format!("[{version:*}]"), "[v0.1]");
+        assert_eq!(Formattable::formati(version), "v0.1");
+    }
+
+    #[test]
+    fn test_formattable_dedup() {
+        let pkg = package(Some("rc1"));
+
+        // one call per distinct expression, however often it is written
+        let s = format!("{pkg.version:*} = {pkg.version:*} = {pkg .version:*}");
+        assert_eq!(s, "v0.1-rc1 = v0.1-rc1 = v0.1-rc1");
+        assert_eq!(pkg.version.renders.get(), 1);
+
+        let (s, audit) = formati::format_audit!("{pkg.version:*}{pkg.version():*}{pkg.version:*}");
+        assert_eq!(s, "v0.1-rc1v0.1-rc1v0.1-rc1");
+        assert_eq!((audit.placeholders(), audit.unique_expressions()), (3, 2));
+    }
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+mod test_io_error {
+    use formati::io_error;
+    use std::cell::Cell;
+    use std::io::{self, ErrorKind};
+
+    struct Request {
+        path: &'static str,
+        attempts: Cell<u32>,
+    }
+
+    impl Request {
+        fn retry(&self) -> u32 {
+            self.attempts.set(self.attempts.get() + 1);
+            self.attempts.get()
+        }
+    }
+
+    fn open(req: &Request) -> io::Result<()> {
+        Err(io_error!(
+            ErrorKind::NotFound,
+            "{req.path} not found (attempt {req.retry()}, {req.retry():03})"
+        ))
+    }
+
+    #[test]
+    fn test_io_error_kind_and_message() {
+        let req = Request {
+            path: "/etc/app.toml",
+            attempts: Cell::new(0),
+        };
+
+        let err = open(&req).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(err.to_string(), "/etc/app.toml not found (attempt 1, 001)");
+        assert_eq!(req.attempts.get(), 1);
+    }
+
+    #[test]
+    fn test_io_error_kind_expression_and_args() {
+        let kinds = [ErrorKind::TimedOut, ErrorKind::Other];
+        let limit = 30;
+
+        let err = io_error!(kinds[0], "gave up after {}s (limit {limit})", limit / 2);
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        assert_eq!(err.to_string(), "gave up after 15s (limit 30)");
+
+        let err = io_error!(kinds[1], "plain message",);
+        assert_eq!(
+            (err.kind(), err.to_string()),
+            (ErrorKind::Other, "plain message".into())
+        );
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(feature = "serde_json")]
+mod test_json {
+    use formati::json_str;
+    use serde_json::{Value, json};
+    use std::cell::Cell;
+
+    struct Record {
+        id: u32,
+        kind: &'static str,
+    }
+
+    #[test]
+    fn test_json_str() {
+        let rec = Record {
+            id: 7,
+            kind: "insert",
+        };
+
+        let value = json_str!("{rec.id}-{rec.kind}");
+        assert_eq!(value, Value::String("7-insert".into()));
+        assert_eq!(value.as_str(), Some("7-insert"));
+
+        let log = json!({ "event": json_str!("{rec.kind} #{rec.id:03}"), "id": rec.id });
+        assert_eq!(log.to_string(), r#"{"event":"insert #007","id":7}"#);
+    }
+
+    #[test]
+    fn test_json_str_dedups_repeated_fields() {
+        let reads = Cell::new(0);
+        let rec = Record {
+            id: 12,
+            kind: "delete",
+        };
+        let current = || {
+            reads.set(reads.get() + 1);
+            &rec
+        };
+
+        let value = json_str!("{current().kind}:{current().id}/{current().kind}");
+        assert_eq!(value, json!("delete:12/delete"));
+        assert_eq!(reads.get(), 2);
+    }
+
+    #[test]
+    fn test_json_macro_in_placeholder() {
+        let rec = Record {
+            id: 3,
+            kind: "update",
+        };
+
+        // `json!` with an object body nests braces and strings in the placeholder
+        let value = json_str!("{json!({ \"id\": rec.id, \"k\": \"}{\" }).to_string()}");
+        assert_eq!(value, Value::String(r#"{"id":3,"k":"}{"}"#.into()));
+        assert_eq!(
+            formati::format!("{json!({ \"kind\": rec.kind })[\"kind\"]}"),
+            "\"update\""
+        );
+    }
+}
@@ -0,0 +1,221 @@
+#[cfg(feature = "log-kv")]
+mod test_log_kv {
+    use log::kv::{Error, Key, Value, VisitSource};
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    use formati::{format, info, kv, trace};
+
+    static LOGGER: OnceLock<TestLogger> = OnceLock::new();
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Captured {
+        target: String,
+        level: Level,
+        message: String,
+        fields: Vec<(String, String)>,
+    }
+
+    #[derive(Clone)]
+    struct TestLogger {
+        captured: Arc<Mutex<Vec<Captured>>>,
+    }
+
+    struct FieldCollector(Vec<(String, String)>);
+
+    impl<'kvs> VisitSource<'kvs> for FieldCollector {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    impl TestLogger {
+        fn get_instance() -> &'static TestLogger {
+            LOGGER.get_or_init(|| {
+                let logger = TestLogger {
+                    captured: Arc::new(Mutex::new(Vec::new())),
+                };
+                let _ = log::set_boxed_logger(Box::new(logger.clone()));
+                log::set_max_level(LevelFilter::Trace);
+                logger
+            })
+        }
+
+        fn take(&self) -> Vec<Captured> {
+            std::mem::take(&mut *self.captured.lock().unwrap())
+        }
+    }
+
+    impl Log for TestLogger {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            let mut fields = FieldCollector(Vec::new());
+            record.key_values().visit(&mut fields).unwrap();
+
+            self.captured.lock().unwrap().push(Captured {
+                target: record.target().to_string(),
+                level: record.level(),
+                message: format!("{record.args()}"),
+                fields: fields.0,
+            });
+        }
+
+        fn flush(&self) {}
+    }
+
+    struct Request {
+        id: u64,
+        path: &'static str,
+        user: (&'static str, u32),
+    }
+
+    const REQUEST: Request = Request {
+        id: 7,
+        path: "/api/users",
+        user: ("alice", 42),
+    };
+
+    #[test]
+    fn test_kv_fields_and_message() {
+        let logger = TestLogger::get_instance();
+        logger.take();
+
+        let req = REQUEST;
+        kv!(
+            Level::Info,
+            request_id = req.id,
+            user_id = req.user.1,
+            "Handled {req.path} for {req.user.0} ({req.user.0})"
+        );
+
+        let logs = logger.take();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].level, Level::Info);
+        assert_eq!(logs[0].message, "Handled /api/users for alice (alice)");
+        assert_eq!(
+            logs[0].fields,
+            vec![
+                ("request_id".to_string(), "7".to_string()),
+                ("user_id".to_string(), "42".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kv_target_and_trailing_comma() {
+        let logger = TestLogger::get_instance();
+        logger.take();
+
+        let req = REQUEST;
+        kv!(
+            target: "http",
+            Level::Warn,
+            path = req.path,
+            "Slow request {req.id} for {req.user.0}",
+        );
+
+        let logs = logger.take();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].target, "http");
+        assert_eq!(logs[0].level, Level::Warn);
+        assert_eq!(logs[0].message, "Slow request 7 for alice");
+        assert_eq!(
+            logs[0].fields,
+            vec![("path".to_string(), "/api/users".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_kv_named_message_args() {
+        let logger = TestLogger::get_instance();
+        logger.take();
+
+        let req = REQUEST;
+        kv!(
+            target: "http",
+            Level::Warn,
+            path = req.path,
+            "Slow request {req.id} for {req.user.0} took {ms}ms",
+            ms = 250,
+        );
+
+        let logs = logger.take();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].target, "http");
+        assert_eq!(logs[0].level, Level::Warn);
+        assert_eq!(logs[0].message, "Slow request 7 for alice took 250ms");
+        assert_eq!(
+            logs[0].fields,
+            vec![("path".to_string(), "/api/users".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_kv_without_fields() {
+        let logger = TestLogger::get_instance();
+        logger.take();
+
+        let req = REQUEST;
+        kv!(Level::Debug, "No fields for {req.user.0}");
+
+        let logs = logger.take();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].message, "No fields for alice");
+        assert!(logs[0].fields.is_empty());
+    }
+
+    #[test]
+    fn test_level_macro_target_fields_and_message() {
+        let logger = TestLogger::get_instance();
+        logger.take();
+
+        struct Statement {
+            name: &'static str,
+            rows: usize,
+        }
+
+        let stmt = Statement {
+            name: "select_users",
+            rows: 3,
+        };
+        let id = 19;
+
+        trace!(
+            target: "db",
+            query_id = id,
+            rows = stmt.rows,
+            "ran {stmt.name} ({stmt.rows} rows, {stmt.name.len()} chars)"
+        );
+        info!(target: "db", "done with {stmt.name}");
+        info!(user = REQUEST.user.0, "{REQUEST.path}");
+
+        let logs = logger.take();
+        assert_eq!(logs.len(), 3);
+
+        assert_eq!(logs[0].target, "db");
+        assert_eq!(logs[0].level, Level::Trace);
+        assert_eq!(logs[0].message, "ran select_users (3 rows, 12 chars)");
+        assert_eq!(
+            logs[0].fields,
+            vec![
+                ("query_id".to_string(), "19".to_string()),
+                ("rows".to_string(), "3".to_string()),
+            ]
+        );
+
+        assert_eq!(logs[1].target, "db");
+        assert_eq!(logs[1].level, Level::Info);
+        assert_eq!(logs[1].message, "done with select_users");
+        assert!(logs[1].fields.is_empty());
+
+        assert_eq!(logs[2].message, "/api/users");
+        assert_eq!(
+            logs[2].fields,
+            vec![("user".to_string(), "alice".to_string())]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "alloc")]
+// Templates forwarded through user `macro_rules!` wrappers arrive wrapped in
+// invisible groups and carry the caller's hygiene
+
+mod test_macro_rules {
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    macro_rules! show_literal {
+        ($fmt:literal) => {
+            formati::format!($fmt)
+        };
+        ($fmt:literal, $($args:tt)*) => {
+            formati::format!($fmt, $($args)*)
+        };
+    }
+
+    macro_rules! show_tt {
+        ($fmt:tt) => {
+            formati::format!($fmt)
+        };
+    }
+
+    macro_rules! show_expr {
+        ($fmt:expr) => {
+            formati::format!($fmt)
+        };
+    }
+
+    macro_rules! append {
+        ($dst:expr, $fmt:literal) => {
+            formati::write!($dst, $fmt)
+        };
+    }
+
+    #[test]
+    fn test_macro_rules_literal() {
+        let point = Point { x: 1, y: 2 };
+
+        assert_eq!(show_literal!("({point.x}, {point.y})"), "(1, 2)");
+        assert_eq!(
+            show_literal!("{point.x} {} {label}", point.y, label = "y"),
+            "1 2 y"
+        );
+    }
+
+    #[test]
+    fn test_macro_rules_tt() {
+        let point = Point { x: 3, y: 4 };
+
+        assert_eq!(show_tt!("{point.x + point.y:>3}"), "  7");
+
+        // `end` follows `..`, so it still resolves as the caller's variable
+        let items = [1, 2, 3];
+        let end = 2;
+        assert_eq!(show_tt!("{items[..end].len()}"), "2");
+    }
+
+    #[test]
+    fn test_macro_rules_expr() {
+        let point = Point { x: 5, y: 6 };
+
+        assert_eq!(show_expr!("{point.x}-{point.y}"), "5-6");
+    }
+
+    #[test]
+    fn test_macro_rules_write() {
+        let point = Point { x: 7, y: 8 };
+        let mut out = String::new();
+
+        append!(&mut out, "{point.x},{point.y}").unwrap();
+        assert_eq!(out, "7,8");
+    }
+
+    #[test]
+    fn test_macro_rules_nested() {
+        macro_rules! outer {
+            ($fmt:literal) => {
+                show_literal!($fmt)
+            };
+        }
+
+        let point = Point { x: 9, y: 10 };
+
+        assert_eq!(outer!("{point.x}/{point.y}"), "9/10");
+    }
+}
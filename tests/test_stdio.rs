@@ -1,6 +1,7 @@
 #![cfg(feature = "stdio")]
 mod test_stdio {
-    use formati::{print, println};
+    use formati::{eprint, eprintln, print, println};
+    use std::alloc::{GlobalAlloc, Layout, System};
     use std::fs::{read_to_string, remove_file};
     use std::io::{self, Write};
     use std::path::PathBuf;
@@ -8,10 +9,35 @@ mod test_stdio {
         atomic::{AtomicUsize, Ordering},
         Mutex,
     };
-    use stdio_override::StdoutOverride;
+    use stdio_override::{StderrOverride, StdoutOverride};
 
     static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
 
+    /// Counts the allocations made on the current thread
+    struct CountingAlloc;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAlloc = CountingAlloc;
+
+    fn allocations() -> usize {
+        ALLOCATIONS.with(|n| n.get())
+    }
+
     // Generate a unique tmp‑file path for every capture.
     fn temp_path() -> PathBuf {
         static COUNTER: AtomicUsize = AtomicUsize::new(0);
@@ -48,6 +74,31 @@ mod test_stdio {
         contents
     }
 
+    /// Like [`with_captured_stdout`], for stderr
+    fn with_captured_stderr<F>(f: F) -> String
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let _lock = CAPTURE_LOCK.lock().unwrap();
+
+        let path = temp_path();
+        io::stderr().flush().ok();
+        let guard = StderrOverride::from_file(&path).expect("override failed");
+
+        std::thread::spawn(move || {
+            f();
+            io::stderr().flush().ok();
+        })
+        .join()
+        .expect("thread panicked in with_captured_stderr");
+
+        drop(guard);
+
+        let contents = read_to_string(&path).expect("read capture file");
+        let _ = remove_file(&path);
+        contents
+    }
+
     // Tests
 
     #[test]
@@ -105,4 +156,178 @@ mod test_stdio {
         assert!(out.contains("Point: (3.1, 2.71)"));
         assert!(out.contains("Normalized: (3.1/5.81, 2.71/5.81)"));
     }
+
+    #[test]
+    fn test_println_s_returns_string() {
+        use formati::{print_s, println_s};
+        use std::cell::Cell;
+
+        struct Counter {
+            calls: Cell<u32>,
+        }
+
+        impl Counter {
+            fn next(&self) -> u32 {
+                self.calls.set(self.calls.get() + 1);
+                self.calls.get()
+            }
+        }
+
+        let out = with_captured_stdout(move || {
+            let counter = Counter {
+                calls: Cell::new(0),
+            };
+
+            let s = println_s!("first={counter.next()} again={counter.next()}");
+            assert_eq!(s, "first=1 again=1");
+            assert_eq!(counter.calls.get(), 1);
+
+            let s = print_s!("then={counter.next()}");
+            assert_eq!(s, "then=2");
+            assert_eq!(counter.calls.get(), 2);
+        });
+
+        assert_eq!(out, "first=1 again=1\nthen=2");
+    }
+
+    #[test]
+    fn test_tee_prints_and_returns() {
+        use formati::tee;
+        use std::cell::Cell;
+
+        struct Stage {
+            name: &'static str,
+            runs: Cell<u32>,
+        }
+
+        impl Stage {
+            fn run(&self, input: Vec<u32>) -> Vec<u32> {
+                self.runs.set(self.runs.get() + 1);
+                input.into_iter().map(|x| x * 2).collect()
+            }
+        }
+
+        let out = with_captured_stderr(move || {
+            let stage = Stage {
+                name: "double",
+                runs: Cell::new(0),
+            };
+            let tmp = "shadowed";
+
+            let total: u32 = tee!(
+                stage.run(vec![1, 2, 3]),
+                "after {stage.name} ({stage.name.len()} chars, {tmp})"
+            )
+            .iter()
+            .sum();
+            assert_eq!(total, 12);
+            assert_eq!(stage.runs.get(), 1);
+
+            let name = tee!(stage.name);
+            assert_eq!(name, "double");
+        });
+
+        assert_eq!(
+            out,
+            "after double (6 chars, shadowed): [2, 4, 6]\n\"double\"\n"
+        );
+    }
+
+    #[test]
+    fn test_dbg_passes_placeholder_values() {
+        use formati::dbg;
+        use std::cell::Cell;
+
+        struct Counter {
+            label: &'static str,
+            hits: Cell<u32>,
+        }
+
+        impl Counter {
+            fn bump(&self) -> u32 {
+                self.hits.set(self.hits.get() + 1);
+                self.hits.get()
+            }
+        }
+
+        let out = with_captured_stderr(move || {
+            let counter = Counter {
+                label: "req",
+                hits: Cell::new(0),
+            };
+
+            // the literal, then each distinct expression once, then the rest
+            let (lit, label, hits, extra) =
+                dbg!("{counter.label}: {counter.bump()} / {counter.bump()}", 7);
+            assert_eq!(lit, "{counter.label}: {counter.bump()} / {counter.bump()}");
+            assert_eq!((label, hits, extra), ("req", 1, 7));
+            assert_eq!(counter.hits.get(), 1);
+
+            // anything else is plain `std::dbg!`
+            assert_eq!(dbg!(counter.label.len()), 3);
+        });
+
+        let lines: Vec<&str> = out
+            .lines()
+            .map(|line| line.split_once("] ").map_or(line, |(_, rest)| rest))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "\"{counter.label}: {counter.bump()} / {counter.bump()}\" = \"{counter.label}: {counter.bump()} / {counter.bump()}\"",
+                "counter.label = \"req\"",
+                "counter.bump() = 1",
+                "7 = 7",
+                "counter.label.len() = 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_does_not_allocate() {
+        struct Request {
+            path: &'static str,
+            status: u16,
+        }
+
+        let out = with_captured_stdout(|| {
+            let req = Request {
+                path: "/health",
+                status: 200,
+            };
+            // the first print sets up stdout's buffer
+            print!("");
+
+            // the template goes straight to `std::println!`, whose
+            // `format_args!` writes into stdout without an intermediate `String`
+            let before = allocations();
+            println!("{req.path} -> {req.status} ({req.path.len()} bytes)");
+            print!("{req.status:>5}|");
+            assert_eq!(allocations(), before);
+
+            // unlike formatting first, which the counter does see
+            let before = allocations();
+            let status = std::format!("{}", req.status);
+            std::println!("{status}");
+            assert!(allocations() > before);
+        });
+
+        assert_eq!(out, "/health -> 200 (7 bytes)\n  200|200\n");
+
+        let err = with_captured_stderr(|| {
+            let req = Request {
+                path: "/health",
+                status: 503,
+            };
+            eprint!("");
+
+            // stderr gets the same `format_args!` straight from `std::eprintln!`
+            let before = allocations();
+            eprintln!("{req.path} -> {req.status} ({req.path.len()} bytes)");
+            eprint!("{req.status:>5}|");
+            assert_eq!(allocations(), before);
+        });
+
+        assert_eq!(err, "/health -> 503 (7 bytes)\n  503|");
+    }
 }
@@ -0,0 +1,26 @@
+#![cfg(feature = "strict")]
+
+mod test_strict {
+    use formati::format;
+
+    #[test]
+    fn test_strict_checks_are_transparent() {
+        let value = 255_u8;
+        let pair = (1.5_f64, "x");
+        let level = (value,);
+
+        // one binding used with several traits
+        assert_eq!(
+            format!("{level.0} {level.0:?} {level.0:#x} {level.0:b} {level.0:o}"),
+            "255 255 0xff 11111111 377"
+        );
+        assert_eq!(
+            format!("{pair.0:e} {pair.1:?} {pair.1:>3}"),
+            "1.5e0 \"x\"   x"
+        );
+
+        // `{:p}` still formats the reference itself
+        let ptr = &value;
+        assert_eq!(format!("{&*ptr:p}"), std::format!("{:p}", ptr));
+    }
+}
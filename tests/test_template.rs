@@ -0,0 +1,56 @@
+#![cfg(feature = "alloc")]
+mod test_template {
+    use formati::{format_const, template};
+
+    struct User {
+        id: u32,
+        name: &'static str,
+    }
+
+    template!(
+        /// Shown on the landing page
+        GREETING = "Hello {user.name} (#{user.id})"
+    );
+    template!(pub(crate) ROW = "{user.name:<8}|{user.id:>4}|{}");
+
+    #[test]
+    fn test_format_const_basic() {
+        let user = User {
+            id: 7,
+            name: "alice",
+        };
+
+        assert_eq!(format_const!(GREETING), "Hello alice (#7)");
+        // the const is an ordinary `&str` holding the raw template
+        assert_eq!(GREETING, "Hello {user.name} (#{user.id})");
+    }
+
+    #[test]
+    fn test_format_const_with_args_and_specs() {
+        let user = User {
+            id: 42,
+            name: "bob",
+        };
+
+        assert_eq!(format_const!(ROW, user.id * 2), "bob     |  42|84");
+        assert_eq!(format_const!(ROW, "x",), "bob     |  42|x");
+    }
+
+    #[test]
+    fn test_format_const_resolves_at_call_site() {
+        template!(LOCAL = "{count}/{items.len()}/{items.len()}");
+
+        // the same template against different variables in different scopes
+        let (count, items) = (1, vec!['a', 'b']);
+        assert_eq!(format_const!(LOCAL), "1/2/2");
+
+        {
+            let count = "many";
+            let items = "abcd";
+            assert_eq!(format_const!(LOCAL), "many/4/4");
+        }
+
+        let render = |count: u8, items: &[u8]| format_const!(LOCAL);
+        assert_eq!(render(3, &[1, 2, 3]), "3/3/3");
+    }
+}
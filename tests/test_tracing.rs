@@ -60,6 +60,7 @@ mod test_tracing {
             .with_max_level(Level::TRACE)
             .with_writer(writer.clone())
             .with_span_events(FmtSpan::NONE)
+            .with_ansi(false)
             .finish();
 
         let guard = tracing::subscriber::set_default(subscriber);
@@ -138,4 +139,109 @@ mod test_tracing {
         let output = writer.captured_output();
         assert!(output.contains("Complex info: Alice is a Engineer living in Anytown, USA"));
     }
+
+    #[test]
+    fn test_tracing_trailing_format_args() {
+        let order = (17, "pending");
+
+        // several named arguments after the message, with a trailing comma
+        let (writer, _guard) = setup_tracing();
+        info!(
+            "Order {order.0} is {state} in {region}",
+            state = order.1,
+            region = "eu",
+        );
+        let output = writer.captured_output();
+        assert!(output.contains("Order 17 is pending in eu"));
+
+        // a positional argument after the message
+        let (writer, _guard) = setup_tracing();
+        warn!("Order {order.0}: {} retries left", 3 - 1);
+        let output = writer.captured_output();
+        assert!(output.contains("Order 17: 2 retries left"));
+
+        // named arguments written before positional ones are reordered for `format_args!`
+        let (writer, _guard) = setup_tracing();
+        error!(
+            order_id = order.0,
+            "Order {order.0} failed: {reason} ({}/{})",
+            reason = "timeout",
+            1,
+            3,
+        );
+        let output = writer.captured_output();
+        assert!(output.contains("Order 17 failed: timeout (1/3)"));
+        assert!(output.contains("order_id"));
+    }
+
+    #[test]
+    fn test_tracing_first_literal_is_template() {
+        let config = ("app.toml", 3);
+
+        // later string literals are format arguments, not the template
+        let (writer, _guard) = setup_tracing();
+        info!("Loaded {config.0} from {} ({})", "disk", "cached");
+        let output = writer.captured_output();
+        assert!(output.contains("Loaded app.toml from disk (cached)"));
+
+        let (writer, _guard) = setup_tracing();
+        debug!(
+            target: "config",
+            sections = config.1,
+            "Parsed {config.1} sections of {}",
+            "app.toml",
+        );
+        let output = writer.captured_output();
+        assert!(output.contains("Parsed 3 sections of app.toml"));
+
+        // events without a message are passed through
+        let (writer, _guard) = setup_tracing();
+        warn!(sections = config.1, path = config.0);
+        let output = writer.captured_output();
+        assert!(output.contains("sections"));
+        assert!(output.contains("app.toml"));
+    }
+
+    #[test]
+    fn test_trace_ret() {
+        use formati::trace_ret;
+        use std::cell::Cell;
+
+        struct Parser {
+            name: &'static str,
+            calls: Cell<u32>,
+        }
+
+        impl Parser {
+            fn parse(&self, src: &str) -> Vec<u32> {
+                self.calls.set(self.calls.get() + 1);
+                src.split(',').map(|n| n.parse().unwrap()).collect()
+            }
+        }
+
+        let parser = Parser {
+            name: "csv",
+            calls: Cell::new(0),
+        };
+
+        let (writer, _guard) = setup_tracing();
+        let total: u32 = trace_ret!(
+            parser.parse("1,2,3"),
+            "{parser.name} parsed {parser.name.len()} chars"
+        )
+        .iter()
+        .sum();
+        assert_eq!(total, 6);
+        assert_eq!(parser.calls.get(), 1);
+
+        let output = writer.captured_output();
+        assert!(output.contains("TRACE"));
+        assert!(output.contains("csv parsed 3 chars return=[1, 2, 3]"));
+
+        // without a message only the value is logged
+        let (writer, _guard) = setup_tracing();
+        let name = trace_ret!(parser.name);
+        assert_eq!(name, "csv");
+        assert!(writer.captured_output().contains("return=\"csv\""));
+    }
 }
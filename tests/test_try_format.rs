@@ -0,0 +1,149 @@
+#![cfg(feature = "alloc")]
+mod test_try_format {
+    use formati::{FormatError, TryDisplay, try_format};
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::fmt;
+
+    /// A user id rendered as the user's name, which may be missing
+    struct UserRef<'a> {
+        id: u32,
+        names: &'a HashMap<u32, &'static str>,
+    }
+
+    impl TryDisplay for UserRef<'_> {
+        fn try_fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), FormatError> {
+            match self.names.get(&self.id) {
+                Some(name) => Ok(f.pad(name)?),
+                None => Err(FormatError::new(format_args!("no user {}", self.id))),
+            }
+        }
+    }
+
+    impl fmt::Debug for UserRef<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "UserRef({})", self.id)
+        }
+    }
+
+    impl fmt::LowerHex for UserRef<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::LowerHex::fmt(&self.id, f)
+        }
+    }
+
+    struct Comment<'a> {
+        author: UserRef<'a>,
+        text: &'static str,
+        renders: Cell<u32>,
+    }
+
+    impl Comment<'_> {
+        fn likes(&self) -> u32 {
+            self.renders.set(self.renders.get() + 1);
+            3
+        }
+    }
+
+    fn names() -> HashMap<u32, &'static str> {
+        HashMap::from([(1, "alice"), (2, "bob")])
+    }
+
+    #[test]
+    fn test_try_format_succeeds() {
+        let names = names();
+        let comment = Comment {
+            author: UserRef {
+                id: 1,
+                names: &names,
+            },
+            text: "hi",
+            renders: Cell::new(0),
+        };
+
+        let s = try_format!(
+            "[{comment.author:>7}] {comment.text:?} ({comment.likes()} likes, {comment.likes():02})"
+        );
+        assert_eq!(s, Ok(String::from("[  alice] \"hi\" (3 likes, 03)")));
+        assert_eq!(comment.renders.get(), 1);
+
+        // `Debug` of a `TryDisplay` value is its plain `Debug`
+        assert_eq!(
+            try_format!("{comment.author:?} by {who}", who = comment.author.id),
+            Ok(String::from("UserRef(1) by 1"))
+        );
+        assert_eq!(
+            try_format!("no {{placeholders}}"),
+            Ok(String::from("no {placeholders}"))
+        );
+    }
+
+    #[test]
+    fn test_try_format_fails() {
+        let names = names();
+        let comment = Comment {
+            author: UserRef {
+                id: 7,
+                names: &names,
+            },
+            text: "hi",
+            renders: Cell::new(0),
+        };
+        let reply = UserRef {
+            id: 2,
+            names: &names,
+        };
+
+        let err = try_format!("{reply} replied to {comment.author}: {comment.text}").unwrap_err();
+        assert_eq!(err.message(), "no user 7");
+        assert_eq!(err.to_string(), "no user 7");
+
+        // the first failure wins
+        let other = UserRef {
+            id: 9,
+            names: &names,
+        };
+        assert_eq!(
+            try_format!("{other} and {comment.author}"),
+            Err(FormatError::new("no user 9"))
+        );
+    }
+
+    #[test]
+    fn test_try_format_display_error() {
+        struct Broken;
+
+        impl fmt::Display for Broken {
+            fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Err(fmt::Error)
+            }
+        }
+
+        // a plain `Display` failure is reported too, though without a message
+        // of its own
+        assert_eq!(
+            try_format!("value: {Broken}").unwrap_err(),
+            FormatError::from(fmt::Error)
+        );
+    }
+
+    #[test]
+    fn test_try_format_other_traits() {
+        let names = names();
+        let unnamed = UserRef {
+            id: 26,
+            names: &names,
+        };
+
+        // only `{}` goes through `TryDisplay`, so an id without a name still
+        // renders in hex and as `Debug`
+        assert_eq!(
+            try_format!("{unnamed:x} {unnamed:#06x} {unnamed:?}"),
+            Ok("1a 0x001a UserRef(26)".to_string())
+        );
+        assert_eq!(
+            try_format!("{unnamed:x} {unnamed}"),
+            Err(FormatError::new("no user 26"))
+        );
+    }
+}
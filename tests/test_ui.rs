@@ -0,0 +1,23 @@
+// Compile-fail cases; the expected diagnostics live next to each case in `tests/ui`
+
+mod test_ui {
+    #[test]
+    fn test_ui_compile_errors() {
+        let t = trybuild::TestCases::new();
+        t.compile_fail("tests/ui/*.rs");
+        #[cfg(feature = "alloc")]
+        t.compile_fail("tests/ui/alloc/*.rs");
+        #[cfg(not(feature = "alloc"))]
+        t.compile_fail("tests/ui/no_alloc/*.rs");
+        #[cfg(feature = "strict")]
+        t.compile_fail("tests/ui/strict/*.rs");
+        #[cfg(feature = "tracing")]
+        t.compile_fail("tests/ui/tracing/*.rs");
+        #[cfg(feature = "chrono")]
+        t.compile_fail("tests/ui/chrono/*.rs");
+        #[cfg(not(feature = "chrono"))]
+        t.compile_fail("tests/ui/no_chrono/*.rs");
+        #[cfg(not(feature = "color"))]
+        t.compile_fail("tests/ui/no_color/*.rs");
+    }
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "std")]
+mod test_vformat {
+    use formati::{MissingValue, vformat};
+    use std::collections::{BTreeMap, HashMap};
+    use std::fmt::Display;
+
+    #[test]
+    fn test_vformat_slice_table() {
+        let user = (String::from("Alice"), 42);
+        let table: Vec<(&str, &dyn Display)> = vec![("name", &user.0), ("id", &user.1)];
+
+        let result = vformat!(table, "User {name} has ID {id}").unwrap();
+        assert_eq!(result, "User Alice has ID 42");
+    }
+
+    #[test]
+    fn test_vformat_hashmap_table() {
+        let mut table = HashMap::new();
+        table.insert("host", String::from("localhost"));
+        table.insert("port", String::from("8080"));
+
+        let result = vformat!(table, "http://{host}:{port}/").unwrap();
+        assert_eq!(result, "http://localhost:8080/");
+    }
+
+    #[test]
+    fn test_vformat_owned_keys() {
+        let mut table = BTreeMap::new();
+        table.insert(String::from("count"), 3);
+        table.insert(String::from("total"), 10);
+
+        let result = vformat!(&table, "{count}/{total}").unwrap();
+        assert_eq!(result, "3/10");
+    }
+
+    #[test]
+    fn test_vformat_specs_and_repeats() {
+        let pi = std::f64::consts::PI;
+        let table: [(&str, &dyn Display); 2] = [("pi", &pi), ("label", &"pi")];
+
+        let result = vformat!(table, "[{label:>4}] {pi:.2} ~ {pi:.4} {{{label}}}").unwrap();
+        assert_eq!(result, "[  pi] 3.14 ~ 3.1416 {pi}");
+    }
+
+    #[test]
+    fn test_vformat_runtime_built_table() {
+        let fields = ["a", "b", "c"];
+        let table: Vec<(String, usize)> = fields
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.to_string(), idx * 10))
+            .collect();
+
+        let result = vformat!(table, "{c}-{b}-{a}").unwrap();
+        assert_eq!(result, "20-10-0");
+    }
+
+    #[test]
+    fn test_vformat_missing_name() {
+        let table: [(&str, &dyn Display); 1] = [("name", &"Alice")];
+
+        let err: MissingValue = vformat!(table, "{name} <{email}>").unwrap_err();
+        assert_eq!(err.name(), "email");
+        assert_eq!(err.to_string(), "no value named `email` in vformat! table");
+    }
+
+    #[test]
+    fn test_vformat_no_placeholders() {
+        let table: [(&str, &dyn Display); 0] = [];
+
+        let result = vformat!(table, "plain text").unwrap();
+        assert_eq!(result, "plain text");
+    }
+}
@@ -0,0 +1,63 @@
+mod test_wrap_macro {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static LINES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// A user macro taking std `format!`-style arguments
+    macro_rules! record {
+        ($($arg:tt)*) => {
+            LINES.with(|lines| lines.borrow_mut().push(format!($($arg)*)))
+        };
+    }
+
+    macro_rules! shout {
+        ($($arg:tt)*) => {
+            format!($($arg)*).to_uppercase()
+        };
+    }
+
+    formati::wrap_macro! {
+        record_f => record;
+        shout_f => shout;
+        concat_f => ::std::format
+    }
+
+    struct Order {
+        id: u32,
+        items: Vec<&'static str>,
+    }
+
+    impl Order {
+        fn total(&self) -> usize {
+            self.items.len() * 10
+        }
+    }
+
+    #[test]
+    fn test_wrap_macro_dot_notation() {
+        let order = Order {
+            id: 7,
+            items: vec!["tea", "cake"],
+        };
+
+        record_f!("order {order.id}: {order.items.len()} items");
+        record_f!(
+            "{order.items[0]}, {} and {rest}",
+            order.items[1],
+            rest = "more"
+        );
+        record_f!("total {order.total():>4}");
+
+        LINES.with(|lines| {
+            assert_eq!(
+                *lines.borrow(),
+                ["order 7: 2 items", "tea, cake and more", "total   20"]
+            );
+        });
+
+        assert_eq!(shout_f!("{order.items[1]} #{order.id}"), "CAKE #7");
+        assert_eq!(concat_f!("{order.items.join(\"+\")}"), "tea+cake");
+    }
+}
@@ -0,0 +1,310 @@
+mod test_write {
+    use formati::{fmt_writeln, write, writeln};
+    use std::fmt::{self, Write};
+
+    struct Record {
+        id: u32,
+        kind: String,
+    }
+
+    impl Record {
+        fn new(id: u32, kind: &str) -> Self {
+            Self {
+                id,
+                kind: kind.into(),
+            }
+        }
+    }
+
+    struct Context {
+        out: String,
+        lines: usize,
+    }
+
+    impl Context {
+        fn new() -> Self {
+            Self {
+                out: String::new(),
+                lines: 0,
+            }
+        }
+
+        fn sink(&mut self) -> &mut impl Write {
+            self.lines += 1;
+            &mut self.out
+        }
+    }
+
+    #[test]
+    fn test_write_to_field_access() {
+        let rec = Record::new(7, "insert");
+        let mut ctx = Context::new();
+
+        write!(ctx.out, "{rec.id}").unwrap();
+        write!(ctx.out, " {rec.kind}:{rec.id:03}").unwrap();
+
+        assert_eq!(ctx.out, "7 insert:007");
+    }
+
+    #[test]
+    fn test_write_to_method_result() {
+        let rec = Record::new(12, "delete");
+        let mut ctx = Context::new();
+
+        writeln!(ctx.sink(), "{rec.kind} #{rec.id}").unwrap();
+        writeln!(ctx.sink(), "done with {rec.id}").unwrap();
+
+        // the writer expression is evaluated once per call, never as an argument
+        assert_eq!(ctx.lines, 2);
+        assert_eq!(ctx.out, "delete #12\ndone with 12\n");
+    }
+
+    #[test]
+    fn test_write_destination_not_a_format_argument() {
+        let rec = Record::new(3, "update");
+        let mut ctx = Context::new();
+
+        // `ctx.out` would be extracted if it were part of the template
+        write!(ctx.out, "{rec.kind}").unwrap();
+        assert_eq!(ctx.out, "update");
+
+        writeln!(ctx.out).unwrap();
+        assert_eq!(ctx.out, "update\n");
+    }
+
+    #[test]
+    fn test_write_trailing_args() {
+        let rec = Record::new(5, "read");
+        let mut out = String::new();
+
+        write!(out, "{rec.kind}={value} ({})", rec.id * 2, value = rec.id).unwrap();
+        assert_eq!(out, "read=5 (10)");
+    }
+
+    #[test]
+    fn test_write_in_display_impl() {
+        struct Wrapper(Record);
+
+        impl fmt::Display for Wrapper {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "<{self.0.kind} {self.0.id}>")
+            }
+        }
+
+        let wrapper = Wrapper(Record::new(9, "scan"));
+        assert_eq!(wrapper.to_string(), "<scan 9>");
+    }
+
+    /// Accepts `limit` bytes, then fails every write
+    struct Limited {
+        out: String,
+        limit: usize,
+    }
+
+    impl Write for Limited {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if self.out.len() + s.len() > self.limit {
+                return Err(fmt::Error);
+            }
+            self.out.push_str(s);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_propagates_errors() {
+        use std::cell::Cell;
+
+        struct Report {
+            rec: Record,
+            parts: Cell<u32>,
+        }
+
+        impl Report {
+            fn part(&self) -> u32 {
+                self.parts.set(self.parts.get() + 1);
+                self.parts.get()
+            }
+        }
+
+        impl fmt::Display for Report {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "[{self.part()}] {self.rec.kind};")?;
+                write!(f, "[{self.part()}] {self.rec.id};")?;
+                write!(f, "[{self.part()}] {self.rec.kind}/{self.rec.id}")
+            }
+        }
+
+        let report = Report {
+            rec: Record::new(42, "sync"),
+            parts: Cell::new(0),
+        };
+
+        // enough room for everything
+        let mut out = Limited {
+            out: String::new(),
+            limit: 64,
+        };
+        assert_eq!(write!(out, "{report}"), Ok(()));
+        assert_eq!(out.out, "[1] sync;[2] 42;[3] sync/42");
+
+        // fails partway through the second `write!`, so the third never runs
+        report.parts.set(0);
+        let mut out = Limited {
+            out: String::new(),
+            limit: 12,
+        };
+        assert_eq!(write!(out, "{report}"), Err(fmt::Error));
+        assert_eq!(out.out, "[1] sync;[2");
+        assert_eq!(report.parts.get(), 2);
+    }
+
+    #[test]
+    fn test_fmt_writeln_into_string() {
+        let rec = Record::new(3, "delete");
+        let mut out = String::from("> ");
+
+        fmt_writeln!(
+            out,
+            "{rec.kind.to_uppercase()} #{rec.id} ({rec.kind.len()})"
+        )
+        .unwrap();
+        fmt_writeln!(out).unwrap();
+        assert_eq!(out, "> DELETE #3 (6)\n\n");
+
+        // a `&mut String` binding works without being `mut` itself
+        let sink = &mut out;
+        fmt_writeln!(sink, "{rec.id:>3}").unwrap();
+        assert_eq!(out, "> DELETE #3 (6)\n\n  3\n");
+    }
+
+    #[test]
+    fn test_fmt_writeln_custom_sink() {
+        #[derive(Default)]
+        struct Counter {
+            bytes: usize,
+            text: String,
+        }
+
+        impl Write for Counter {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.bytes += s.len();
+                self.text.push_str(s);
+                Ok(())
+            }
+        }
+
+        let rec = Record::new(12, "update");
+        let mut counter = Counter::default();
+
+        fmt_writeln!(counter, "{rec.kind}:{rec.id}").unwrap();
+        fmt_writeln!(&mut counter, "{rec.kind.len()}").unwrap();
+
+        assert_eq!(counter.text, "update:12\n6\n");
+        assert_eq!(counter.bytes, 12);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_write_len() {
+        use formati::write_len;
+
+        let rec = Record::new(9, "größe");
+        let mut buf = String::from("len=??;");
+
+        let n = write_len!(buf, "{rec.id}:{rec.kind}");
+        // `ö` and `ß` take two bytes each
+        assert_eq!(n, "9:größe".len());
+        assert_eq!(n, 9);
+        assert_eq!(buf, "len=??;9:größe");
+
+        // through a `&mut String`, with a spec and an emoji
+        let sink = &mut buf;
+        assert_eq!(write_len!(sink, "|{rec.kind.chars().count():>3}🦀"), 8);
+        assert_eq!(write_len!(sink, ""), 0);
+        assert_eq!(buf, "len=??;9:größe|  5🦀");
+    }
+
+    // no `Write` trait is imported in here
+    mod without_write_imports {
+        use super::Record;
+        use formati::{write, writeln};
+
+        #[test]
+        fn test_write_string_without_import() {
+            let rec = Record::new(3, "insert");
+            let mut out = String::new();
+
+            write!(out, "{rec.kind}#{rec.id}").unwrap();
+            writeln!(&mut out, " ({rec.kind.len()})").unwrap();
+            writeln!(out).unwrap();
+            assert_eq!(out, "insert#3 (6)\n\n");
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn test_io_write_vec_without_import() -> std::io::Result<()> {
+            use formati::{io_write, io_writeln};
+
+            let rec = Record::new(4, "größe");
+            let mut buf: Vec<u8> = Vec::new();
+
+            io_write!(buf, "{rec.id}:")?;
+            io_writeln!(&mut buf, "{rec.kind.to_uppercase()}")?;
+            io_writeln!(buf)?;
+            let mut cursor = std::io::Cursor::new([0u8; 4]);
+            io_write!(cursor, "{rec.id * 1000}")?;
+
+            assert_eq!(String::from_utf8(buf).unwrap(), "4:GRÖSSE\n\n");
+            assert_eq!(&cursor.into_inner(), b"4000");
+            Ok(())
+        }
+
+        /// Implements both `fmt::Write` and `io::Write`, tagging each side
+        #[cfg(feature = "std")]
+        #[derive(Default)]
+        struct Both {
+            out: String,
+        }
+
+        #[cfg(feature = "std")]
+        impl std::fmt::Write for Both {
+            fn write_str(&mut self, s: &str) -> std::fmt::Result {
+                self.out.push_str(&s.to_lowercase());
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl std::io::Write for Both {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.out
+                    .push_str(&String::from_utf8_lossy(buf).to_uppercase());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn test_write_to_both_traits_destination() {
+            use formati::{io_write, io_writeln};
+
+            let rec = Record::new(5, "Mixed");
+            let mut both = Both::default();
+
+            // `write!` only brings `fmt::Write` into scope, so this is unambiguous
+            let res: std::fmt::Result = write!(both, "{rec.kind}:");
+            res.unwrap();
+            writeln!(both, "{rec.id}").unwrap();
+            let res: std::io::Result<()> = io_write!(both, "{rec.kind}:");
+            res.unwrap();
+            io_writeln!(both, "{rec.id}").unwrap();
+
+            assert_eq!(both.out, "mixed:5\nMIXED:5\n");
+        }
+    }
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let count = 3;
+    let _ = formati::format!("{count:chain}");
+}
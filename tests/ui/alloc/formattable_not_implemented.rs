@@ -0,0 +1,9 @@
+struct Point {
+    x: i32,
+}
+
+fn main() {
+    let point = Point { x: 1 };
+    let _ = formati::format!("{point:*}");
+    let _ = formati::format!("{point.x:*}");
+}
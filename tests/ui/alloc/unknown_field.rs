@@ -0,0 +1,11 @@
+struct User {
+    name: &'static str,
+}
+
+fn main() {
+    let user = User { name: "ada" };
+    // the error points into the template: at `nmae` where the compiler supports
+    // literal subspans, and at the whole literal otherwise
+    let _ = formati::format!("hi {user.nmae.len()} ({user.name})");
+    let _ = formati::format!("hi {user.name.lenn()}");
+}
@@ -0,0 +1,9 @@
+struct Request {
+    trace_id: u64,
+}
+
+fn main() {
+    let req = Request { trace_id: 7 };
+    // a compiled-out head would leave the placeholder without a value
+    let _ = formati::format!("{#[cfg(debug_assertions)] req.trace_id}");
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let now = std::time::SystemTime::now();
+    let _ = formati::format!("at {now:time(%H:%Q)}");
+    let _ = formati::format!("at {now:time(%H:%M %)}");
+}
@@ -0,0 +1,11 @@
+struct Config {
+    retries: u8,
+}
+
+fn main() {
+    let config = Config { retries: 3 };
+    let _ = formati::const_format!("retries={config.retries}");
+    let _ = formati::const_format!("retries={3:>2}");
+    let _ = formati::const_format!("timeout={1e3}ms");
+    let _ = formati::const_format!("offset={-0.0}");
+}
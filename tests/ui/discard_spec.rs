@@ -0,0 +1,4 @@
+fn main() {
+    let items = vec![1, 2];
+    let _ = formati::format!("{_ = items.len():>4}");
+}
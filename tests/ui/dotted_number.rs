@@ -0,0 +1,4 @@
+fn main() {
+    // digits and dots are neither a positional index nor an expression
+    let _ = formati::format!("{1.2.3}");
+}
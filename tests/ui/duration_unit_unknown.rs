@@ -0,0 +1,8 @@
+use std::time::Duration;
+
+fn main() {
+    let elapsed = Duration::from_secs(90);
+    let _ = formati::format!("{elapsed:min}");
+    let _ = formati::format!("{elapsed:ms.two}");
+    let _ = formati::format!("{elapsed:rde}");
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let path = "/tmp/a b";
+
+    let _ = formati::format_shell!("rm {}", path);
+}
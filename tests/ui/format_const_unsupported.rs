@@ -0,0 +1,8 @@
+const PLAIN: &str = "{x}";
+
+fn main() {
+    let x = 1;
+    let _ = formati::format_const!("{x}");
+    let _ = formati::format_const!(concat!("{", "x}"));
+    let _ = formati::format_const!(PLAIN);
+}
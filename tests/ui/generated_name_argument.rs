@@ -0,0 +1,7 @@
+fn main() {
+    let user = (7, "alice");
+    let backup = "bob";
+
+    // without the check, `{user.1}` and this argument would both be `__formati_0`
+    let _ = formati::format!("{user.1} ({user.0})", __formati_0 = backup);
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let items = [1, 2, 3];
+    let _ = formati::format!("{items:join(, )}");
+    let _ = formati::format!("{items:join(\"-\")>4}");
+}
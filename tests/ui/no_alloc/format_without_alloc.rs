@@ -0,0 +1,7 @@
+fn main() {
+    let user = ("ada", 7);
+    let _ = formati::format!("{user.0}#{user.1}");
+    let _ = formati::format_arc!("{user.0}");
+    let _ = formati::format_cow!("static");
+    let _ = formati::format_sep!(", ");
+}
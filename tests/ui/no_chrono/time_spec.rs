@@ -0,0 +1,4 @@
+fn main() {
+    let now = std::time::SystemTime::now();
+    let _ = formati::format!("at {now:time(%H:%M:%S)}");
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let name = "ada";
+    let _ = formati::format!("{name:red.bold}");
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let (v, w) = (1, 4);
+    let _ = formati::format!("{v:{w}");
+    let _ = formati::format!("{v:}}");
+    let _ = formati::format!("[{v.pow(2):{w}}]");
+    let _ = formati::format!("{v.pow(2):>}}");
+}
@@ -0,0 +1,21 @@
+struct Opaque;
+
+struct Reading {
+    sensor: Opaque,
+    value: f64,
+}
+
+impl Reading {
+    fn sensor(&self) -> &Opaque {
+        &self.sensor
+    }
+}
+
+fn main() {
+    let reading = Reading {
+        sensor: Opaque,
+        value: 1.5,
+    };
+
+    let _ = formati::format!("{reading.value:.1} from {reading.sensor():?}");
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let user = (7, "alice");
+    formati::info!("user {user.1} #{}", => user.0);
+}
@@ -0,0 +1,4 @@
+fn main() {
+    // no message at all: forwarded to `tracing`, which rejects it
+    formati::info!();
+}
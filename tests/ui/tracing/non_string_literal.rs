@@ -0,0 +1,5 @@
+fn main() {
+    let user = (7, "alice");
+    // the first string literal is the template, leaving `42` where `tracing` expects fields
+    formati::info!(42, "user {user.1}");
+}
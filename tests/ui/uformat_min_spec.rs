@@ -0,0 +1,13 @@
+struct Reading {
+    sensor: u32,
+    value: f64,
+}
+
+fn main() {
+    let reading = Reading {
+        sensor: 7,
+        value: 21.5,
+    };
+
+    let _ = formati::uformat_min!("sensor {reading.sensor}: {reading.value:.1}");
+}